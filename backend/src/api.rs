@@ -5,7 +5,8 @@ use axum::{
 };
 use crate::models::{Message, MissionState, DashboardData, SystemStatus, DocInfo};
 use crate::storage::{read_json, atomic_write_json, FileLock, get_disk_usage};
-use crate::system::{perform_cleanup, scan_cleanup_candidates};
+use crate::system::perform_cleanup;
+use crate::scan_jobs::{self, ScanJob, ScanKind};
 
 
 use chrono::Local;
@@ -29,7 +30,7 @@ fn is_running(name: &str) -> bool {
 fn is_podman_running(container: &str) -> bool {
     use std::process::Command;
     Command::new("podman")
-        .args(&["ps", "--filter", &format!("name={}", container), "--format", "{{.Names}}"])
+        .args(["ps", "--filter", &format!("name={}", container), "--format", "{{.Names}}"])
         .output()
         .map(|o| {
             let output = String::from_utf8_lossy(&o.stdout);
@@ -60,6 +61,7 @@ pub async fn post_message(Json(mut new_msg): Json<Message>) -> impl IntoResponse
     if let Err(e) = atomic_write_json(LOG_FILE, &messages) {
         return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response();
     }
+    crate::search::index_message(&new_msg);
 
     (StatusCode::CREATED, Json(new_msg)).into_response()
 }
@@ -101,7 +103,7 @@ pub async fn get_dashboard() -> impl IntoResponse {
 
     let mut data: DashboardData = read_json(DASHBOARD_FILE).unwrap_or_default();
     data.projects = read_json::<Vec<crate::models::Project>>(PROJECTS_FILE).unwrap_or_default();
-    data.docs = read_json::<Vec<DocInfo>>(DOCS_FILE).unwrap_or_default();
+    data.docs = crate::doc_meta::refresh_all(read_json::<Vec<DocInfo>>(DOCS_FILE).unwrap_or_default()).await;
     data.activities = vec![]; // Logging is currently disabled
 
     if let Ok(state) = read_json::<MissionState>(STATE_FILE) {
@@ -114,9 +116,15 @@ pub async fn get_dashboard() -> impl IntoResponse {
     let mapper_status = is_podman_running("smartmapper");
 
     // --- Disk Info ---
-    data.disk = get_disk_usage("/home/a2/Desktop/gem");
+    // Measured in the background; 0 until the first scan finishes
+    let (workspace, _) = scan_jobs::cached_or_refresh(ScanKind::WorkspaceSize);
+    data.disk = get_disk_usage(workspace.and_then(|c| c.value.as_u64()).unwrap_or(0));
     // -----------------
 
+    data.sentry_alerts = crate::handle_sentry::latest_report()
+        .map(|r| r.alerts())
+        .unwrap_or_default();
+
     data.systems = vec![
         // --- Server (api.rs) ---
         SystemStatus { 
@@ -190,25 +198,187 @@ pub async fn get_dashboard() -> impl IntoResponse {
 
 
 
+/// 202 for a scan without a cached result yet; poll `/api/scans/{id}` or retry.
+fn scanning_response(job: Option<ScanJob>) -> axum::response::Response {
+    (StatusCode::ACCEPTED, Json(serde_json::json!({
+        "status": "scanning",
+        "job": job,
+    }))).into_response()
+}
+
 pub async fn get_cleanup_candidates() -> impl IntoResponse {
-    let candidates = scan_cleanup_candidates();
-    (StatusCode::OK, Json(candidates)).into_response()
+    match scan_jobs::cached_or_refresh(ScanKind::Cleanup) {
+        (Some(cached), _) => (StatusCode::OK, Json(scan_jobs::cleanup_candidates(&cached))).into_response(),
+        (None, job) => scanning_response(job),
+    }
+}
+
+pub async fn get_cleanup_providers() -> impl IntoResponse {
+    match scan_jobs::cached_or_refresh(ScanKind::Cleanup) {
+        (Some(cached), _) => (StatusCode::OK, Json(cached.value)).into_response(),
+        (None, job) => scanning_response(job),
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct ArtifactsQuery {
+    stale_days: Option<u64>,
+}
+
+pub async fn get_artifacts(axum::extract::Query(params): axum::extract::Query<ArtifactsQuery>) -> impl IntoResponse {
+    let stale_days = params.stale_days.unwrap_or(crate::artifacts::STALE_DAYS);
+    let (cached, job) = scan_jobs::cached_or_refresh(ScanKind::Artifacts { stale_days });
+    let Some(cached) = cached else { return scanning_response(job) };
+    let dirs: Vec<crate::models::ArtifactDir> = serde_json::from_value(cached.value).unwrap_or_default();
+    let total: u64 = dirs.iter().map(|d| d.size).sum();
+    let reclaimable: u64 = dirs.iter().filter(|d| d.reclaimable).map(|d| d.size).sum();
+    (StatusCode::OK, Json(serde_json::json!({
+        "total": total,
+        "reclaimable": reclaimable,
+        "dirs": dirs,
+        "scanned_at": cached.finished_at,
+        "refreshing": job.is_some(),
+    }))).into_response()
+}
+
+#[derive(serde::Deserialize)]
+pub struct DiskTreeQuery {
+    path: Option<String>,
+    depth: Option<usize>,
+}
+
+pub async fn get_disk_tree(axum::extract::Query(params): axum::extract::Query<DiskTreeQuery>) -> impl IntoResponse {
+    let requested = params.path.unwrap_or_else(|| crate::disk_tree::DEFAULT_ROOT.to_string());
+    let path = match std::fs::canonicalize(&requested) {
+        Ok(path) if path.is_dir() => path,
+        Ok(_) => return (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": "Not a directory"}))).into_response(),
+        Err(e) => return (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": e.to_string()}))).into_response(),
+    };
+    match crate::disk_tree::lookup(&path, params.depth.unwrap_or(1)) {
+        crate::disk_tree::TreeLookup::Ready(node) => (StatusCode::OK, Json(serde_json::json!({
+            "status": "ready",
+            "tree": node,
+        }))).into_response(),
+        crate::disk_tree::TreeLookup::Scanning(job) => (StatusCode::ACCEPTED, Json(serde_json::json!({
+            "status": "scanning",
+            "path": path,
+            "job": job,
+        }))).into_response(),
+    }
 }
 
 #[derive(serde::Deserialize)]
 pub struct CleanupParams {
     path: String,
     category: String,
+    #[serde(default)]
+    mode: Option<crate::cleanup::CleanupMode>, // Falls back to the configured default
+    #[serde(default)]
+    limit: Option<usize>, // Max files listed by a dry run
+}
+
+fn cleanup_error_status(e: &std::io::Error) -> StatusCode {
+    match e.kind() {
+        std::io::ErrorKind::PermissionDenied => StatusCode::FORBIDDEN,
+        std::io::ErrorKind::InvalidInput | std::io::ErrorKind::Unsupported => StatusCode::BAD_REQUEST,
+        std::io::ErrorKind::NotFound => StatusCode::NOT_FOUND,
+        std::io::ErrorKind::AlreadyExists | std::io::ErrorKind::ResourceBusy => StatusCode::CONFLICT,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
 }
 
 pub async fn post_cleanup(Json(params): Json<CleanupParams>) -> impl IntoResponse {
     println!("🧹 Cleanup requested for path: {}", params.path);
 
-    let res = perform_cleanup(&params.path, &params.category);
+    let res = tokio::task::spawn_blocking(move || {
+        perform_cleanup(&params.path, &params.category, params.mode, params.limit)
+    }).await.unwrap_or_else(|e| Err(std::io::Error::other(e)));
+
+    match res {
+        Ok(outcome) => {
+            if !matches!(outcome, crate::cleanup::CleanupOutcome::DryRun { .. }) {
+                scan_jobs::invalidate(ScanKind::Cleanup);
+                scan_jobs::invalidate(ScanKind::WorkspaceSize);
+            }
+            let mut body = serde_json::to_value(&outcome).unwrap_or_default();
+            body["status"] = "ok".into();
+            (StatusCode::OK, Json(body)).into_response()
+        }
+        Err(e) => (cleanup_error_status(&e), e.to_string()).into_response(),
+    }
+}
+
+pub async fn get_trash() -> impl IntoResponse {
+    let entries = crate::cleanup::trash::list(&crate::cleanup::CleanupConfig::load().trash);
+    (StatusCode::OK, Json(entries)).into_response()
+}
+
+#[derive(serde::Deserialize)]
+pub struct RestoreParams {
+    id: String,
+}
+
+pub async fn post_disk_restore(Json(params): Json<RestoreParams>) -> impl IntoResponse {
+    println!("♻️ Restoring trashed item {}", params.id);
+
+    match crate::cleanup::trash::restore(&crate::cleanup::CleanupConfig::load().trash, &params.id) {
+        Ok(entry) => {
+            scan_jobs::invalidate(ScanKind::Cleanup);
+            scan_jobs::invalidate(ScanKind::WorkspaceSize);
+            (StatusCode::OK, Json(serde_json::json!({"status": "ok", "restored": entry}))).into_response()
+        }
+        Err(e) => (cleanup_error_status(&e), e.to_string()).into_response(),
+    }
+}
+
+pub async fn get_cleanup_policies() -> impl IntoResponse {
+    use crate::cleanup::policy;
+    let config = crate::cleanup::CleanupConfig::load();
+    let runs = policy::load_runs();
+    let policies: Vec<serde_json::Value> = config.policies.iter().map(|p| {
+        let usage_pct = crate::mounts::fs_space(std::path::Path::new(&p.mount))
+            .map(|(total, free)| crate::mounts::pct(total.saturating_sub(free), total));
+        serde_json::json!({
+            "policy": p,
+            "usage_pct": usage_pct,
+            "last_run": runs.iter().rev().find(|r| r.policy == p.name),
+        })
+    }).collect();
+    (StatusCode::OK, Json(policies)).into_response()
+}
+
+pub async fn get_cleanup_policy_runs() -> impl IntoResponse {
+    let mut runs = crate::cleanup::policy::load_runs();
+    runs.reverse(); // Newest first
+    (StatusCode::OK, Json(runs)).into_response()
+}
+
+#[derive(serde::Deserialize)]
+pub struct PolicyRunParams {
+    name: String,
+    #[serde(default)]
+    dry_run: Option<bool>, // Simulates unless explicitly false
+    #[serde(default)]
+    force: bool, // Run even when usage is below the trigger
+}
+
+pub async fn post_cleanup_policy_run(Json(params): Json<PolicyRunParams>) -> impl IntoResponse {
+    use crate::cleanup::policy::{self, RunTrigger};
+    let config = crate::cleanup::CleanupConfig::load();
+    let Some(found) = config.policies.iter().find(|p| p.name == params.name).cloned() else {
+        return (StatusCode::NOT_FOUND, "No policy with that name").into_response();
+    };
+    let dry_run = params.dry_run.unwrap_or(true);
+    println!("🧹 Policy {} requested{}", found.name, if dry_run { " (dry run)" } else { "" });
+
+    let res = tokio::task::spawn_blocking(move || {
+        policy::evaluate(&config, &found, RunTrigger::Manual, dry_run, params.force)
+    }).await.unwrap_or_else(|e| Err(std::io::Error::other(e)));
 
     match res {
-        Ok(_) => (StatusCode::OK, Json(serde_json::json!({"status": "ok"}))).into_response(),
-        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        Ok(Some(run)) => (StatusCode::OK, Json(serde_json::json!({"status": "ok", "run": run}))).into_response(),
+        Ok(None) => (StatusCode::OK, Json(serde_json::json!({"status": "not_triggered"}))).into_response(),
+        Err(e) => (cleanup_error_status(&e), e.to_string()).into_response(),
     }
 }
 
@@ -231,6 +401,7 @@ pub async fn post_dashboard(Json(data): Json<DashboardData>) -> impl IntoRespons
     let mut persistent_data = data.clone();
     persistent_data.projects = vec![]; // Keep separate
     persistent_data.docs = vec![];     // Keep separate
+    persistent_data.sentry_alerts = vec![]; // Derived from sentry_reports.json
 
     if let Err(e) = atomic_write_json(DASHBOARD_FILE, &persistent_data) {
         return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response();
@@ -298,12 +469,12 @@ pub async fn post_podman(Json(params): Json<PodmanAction>) -> impl IntoResponse
 
     let output = if action == "stop" {
         Command::new("podman")
-            .args(&["stop", "smartmapper"])
+            .args(["stop", "smartmapper"])
             .current_dir("/home/a2/mybook/smart_mapper")
             .output()
     } else {
         Command::new("podman")
-            .args(&["start", "smartmapper"])
+            .args(["start", "smartmapper"])
             .current_dir("/home/a2/mybook/smart_mapper")
             .output()
     };
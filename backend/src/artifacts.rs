@@ -0,0 +1,86 @@
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use walkdir::WalkDir;
+
+use crate::models::ArtifactDir;
+use crate::storage::calculate_dir_size;
+
+// --- Build Artifacts & Dependency Caches ---
+//
+// Finds `target/`, `node_modules/` and virtualenv directories next to the
+// manifest that produced them. Sentry's ignore rules skip these trees, so the
+// scan walks the workspace on its own.
+
+pub const STALE_DAYS: u64 = 30;
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// Directory names that are never part of a project's own sources.
+const SKIPPED_DIRS: &[&str] = &[".git", "target", "node_modules", ".venv", "venv", "__pycache__"];
+
+/// Artifact kind of `dir`, when it is one and its owning project is recognisable.
+pub fn classify(dir: &Path) -> Option<&'static str> {
+    let name = dir.file_name()?.to_str()?;
+    let parent = dir.parent()?;
+    match name {
+        "target" if parent.join("Cargo.toml").is_file() || dir.join("CACHEDIR.TAG").is_file() => Some("cargo-target"),
+        "node_modules" if parent.join("package.json").is_file() => Some("node_modules"),
+        _ if dir.join("pyvenv.cfg").is_file() => Some("python-venv"),
+        _ => None,
+    }
+}
+
+fn mtime_secs(meta: &std::fs::Metadata) -> u64 {
+    meta.modified().ok()
+        .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Newest mtime of the project's own files, skipping artifacts and `.git`.
+pub fn project_touched(project: &Path) -> u64 {
+    WalkDir::new(project).into_iter()
+        .filter_entry(|e| !(e.file_type().is_dir() && e.depth() > 0
+            && e.file_name().to_str().is_some_and(|n| SKIPPED_DIRS.contains(&n))))
+        .flatten()
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| mtime_secs(&m))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Every artifact directory under `root`, reclaimable candidates first, then by size.
+pub fn scan_artifacts(root: &Path, stale_days: u64) -> Vec<ArtifactDir> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let mut found = vec![];
+
+    let mut walker = WalkDir::new(root).into_iter();
+    while let Some(entry) = walker.next() {
+        let Ok(entry) = entry else { continue };
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+        if entry.file_name() == ".git" {
+            walker.skip_current_dir();
+            continue;
+        }
+        let Some(kind) = classify(entry.path()) else { continue };
+        walker.skip_current_dir();
+
+        let project = entry.path().parent().unwrap_or(root);
+        let touched = project_touched(project);
+        let idle_days = now.saturating_sub(touched) / DAY_SECS;
+        found.push(ArtifactDir {
+            path: entry.path().to_string_lossy().into(),
+            kind: kind.into(),
+            project: project.to_string_lossy().into(),
+            size: calculate_dir_size(&entry.path().to_string_lossy()),
+            project_touched: touched,
+            idle_days,
+            reclaimable: idle_days >= stale_days,
+        });
+    }
+
+    found.sort_by(|a, b| b.reclaimable.cmp(&a.reclaimable).then(b.size.cmp(&a.size)));
+    found
+}
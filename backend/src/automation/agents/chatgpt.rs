@@ -70,7 +70,7 @@ pub async fn execute_chatgpt_task(ctx: &AgentContext, prompt: &str) -> AgentResu
     wait_for_settle(&page).await?;
     
     // Capture debug screenshot
-    let screenshot_path = format!("./isolated_data/chatgpt/last_execution.png");
+    let screenshot_path = "./isolated_data/chatgpt/last_execution.png".to_string();
     let _ = std::fs::create_dir_all("./isolated_data/chatgpt");
     let _ = page.save_screenshot(chromiumoxide::cdp::browser_protocol::page::CaptureScreenshotParams::default(), &screenshot_path).await;
     println!("[ChatGPT] Screenshot saved to {}", screenshot_path);
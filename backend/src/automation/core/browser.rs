@@ -25,7 +25,7 @@ impl BrowserActor {
         let (browser, mut handler) = Browser::launch(config).await.map_err(|e| e.to_string())?;
         
         let handler_task = tokio::spawn(async move {
-            while let Some(_) = handler.next().await {}
+            while handler.next().await.is_some() {}
         });
 
         Ok(Self {
@@ -14,6 +14,12 @@ pub struct SessionPool {
     sessions: HashMap<String, SessionEntry>,
 }
 
+impl Default for SessionPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SessionPool {
     pub fn new() -> Self {
         Self {
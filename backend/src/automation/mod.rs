@@ -95,7 +95,7 @@ impl BridgeManager {
         match self.session_pool.get_or_create(agent_name, || config.clone()).await {
             Ok(tx) => {
                 let (reply_tx, reply_rx) = oneshot::channel();
-                if let Ok(_) = tx.send(BrowserCommand::Ping { reply: reply_tx }).await {
+                if tx.send(BrowserCommand::Ping { reply: reply_tx }).await.is_ok() {
                     match tokio::time::timeout(Duration::from_secs(5), reply_rx).await {
                         Ok(Ok(healthy)) => healthy,
                         _ => {
@@ -145,11 +145,11 @@ impl BridgeManager {
                     name != "target" && name != ".git" && name != "node_modules"
                 })
             {
-                if let Ok(entry) = entry {
-                    if entry.file_name().to_string_lossy() == first_arg {
-                        found_path = Some(entry.path().to_path_buf());
-                        break;
-                    }
+                if let Ok(entry) = entry
+                    && entry.file_name().to_string_lossy() == first_arg
+                {
+                    found_path = Some(entry.path().to_path_buf());
+                    break;
                 }
             }
 
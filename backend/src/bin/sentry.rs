@@ -1,20 +1,74 @@
-use backend::sentry::SentryAudit;
+use backend::sentry::baseline::Baseline;
+use backend::sentry::config::SentryConfig;
+use backend::sentry::report::OutputFormat;
+use backend::sentry::{watch, SentryAudit, CONFIG_FILE};
 use std::env;
+use std::path::Path;
 use std::thread;
 use std::time::Duration;
 
+fn arg_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter().position(|a| a == flag).and_then(|i| args.get(i + 1)).map(|s| s.as_str())
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
-    let mut sentry = SentryAudit::new();
+    let config_path = arg_value(&args, "--config").unwrap_or(CONFIG_FILE);
+    let mut sentry = SentryAudit::with_config(SentryConfig::load(Path::new(config_path)));
+    sentry.hash_contents = args.contains(&"--hash".to_string());
+    if let Some(format) = arg_value(&args, "--format") {
+        match OutputFormat::parse(format) {
+            Some(f) => sentry.format = f,
+            None => {
+                eprintln!("Unknown --format '{}' (expected json or text)", format);
+                std::process::exit(2);
+            }
+        }
+    }
+    if let Some(list) = arg_value(&args, "--rules") {
+        let rules = sentry.rules();
+        let known: Vec<&str> = rules.iter().map(|r| r.id()).collect();
+        let selected: Vec<String> = list.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect();
+        if let Some(unknown) = selected.iter().find(|id| !known.contains(&id.as_str())) {
+            eprintln!("Unknown rule '{}' (available: {})", unknown, known.join(", "));
+            std::process::exit(2);
+        }
+        sentry.rule_filter = Some(selected);
+    }
 
-    if args.contains(&"--once".to_string()) {
+    if let Some(i) = args.iter().position(|a| a == "baseline") {
+        let config = &sentry.config.baseline;
+        let result = match args.get(i + 1).map(|a| a.as_str()) {
+            Some("create") => Baseline::create(config)
+                .map(|b| format!("Baseline of {} paths written to {}", b.entries.len(), config.file)),
+            Some("accept") => Baseline::accept(config, args.get(i + 2..).unwrap_or_default())
+                .map(|paths| format!("Accepted {} paths into {}", paths.len(), config.file)),
+            _ => Err("usage: sentry baseline create | accept [path...]".to_string()),
+        };
+        match result {
+            Ok(message) => println!("{}", message),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(2);
+            }
+        }
+    } else if args.contains(&"--list-rules".to_string()) {
+        println!("{}", sentry.describe_rules());
+    } else if let Some(path) = arg_value(&args, "--explain") {
+        println!("{}", sentry.explain_ignore(Path::new(path)));
+    } else if args.contains(&"--diff".to_string()) {
+        println!("{}", sentry.diff_report());
+    } else if args.contains(&"--once".to_string()) {
         let report = sentry.run(true);
-        println!("{}", report);
+        sentry.publish(&report);
+    } else if args.contains(&"--watch".to_string()) {
+        eprintln!("Starting Sentry Audit Daemon (Rust, watch mode)...");
+        watch::run_watch(&mut sentry, Duration::from_secs(60));
     } else {
-        println!("Starting Sentry Audit Daemon (Rust)...");
+        eprintln!("Starting Sentry Audit Daemon (Rust)...");
         loop {
             let report = sentry.run(false);
-            println!("{}", report);
+            sentry.publish(&report);
             thread::sleep(Duration::from_secs(60));
         }
     }
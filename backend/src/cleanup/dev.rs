@@ -0,0 +1,148 @@
+use std::io::Result;
+use std::path::{Path, PathBuf};
+
+use crate::artifacts::{scan_artifacts, STALE_DAYS};
+use crate::cleanup::{candidate, existing_dirs, has_command, run_command, CleanupProvider, HOME, WORKSPACE};
+use crate::models::{CleanupItem, CleanupRisk};
+
+// --- Developer Toolchain Caches ---
+
+/// `target/`, `node_modules/` and virtualenvs of workspace projects idle for a month.
+pub struct BuildArtifacts;
+impl CleanupProvider for BuildArtifacts {
+    fn id(&self) -> &'static str {
+        "artifact"
+    }
+
+    fn name(&self) -> &'static str {
+        "Stale Build Artifacts"
+    }
+
+    fn risk(&self) -> CleanupRisk {
+        CleanupRisk::Low
+    }
+
+    fn method(&self) -> &'static str {
+        "Delete the artifact directory; the next build or install recreates it"
+    }
+
+    fn candidates(&self) -> Vec<CleanupItem> {
+        let workspace = Path::new(WORKSPACE);
+        scan_artifacts(workspace, STALE_DAYS).into_iter()
+            .filter(|d| d.reclaimable)
+            .map(|d| {
+                let rel = d.path.trim_start_matches(WORKSPACE).trim_start_matches('/').to_string();
+                let project = Path::new(&d.project).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                candidate(self, &rel.replace('/', "_"), format!("{} {} (idle {}d)", project, d.kind, d.idle_days), d.path, d.size)
+            })
+            .collect()
+    }
+}
+
+/// Downloaded crates and git checkouts under `~/.cargo`.
+pub struct CargoRegistry;
+impl CleanupProvider for CargoRegistry {
+    fn id(&self) -> &'static str {
+        "cargo-registry"
+    }
+
+    fn name(&self) -> &'static str {
+        "Cargo Registry Cache"
+    }
+
+    fn risk(&self) -> CleanupRisk {
+        CleanupRisk::Low
+    }
+
+    fn method(&self) -> &'static str {
+        "Delete the cache directory; cargo downloads crates again on the next build"
+    }
+
+    fn available(&self) -> bool {
+        Path::new(&format!("{}/.cargo", HOME)).is_dir()
+    }
+
+    fn candidates(&self) -> Vec<CleanupItem> {
+        existing_dirs(self, &[
+            ("cache", "Cargo crate archives", format!("{}/.cargo/registry/cache", HOME)),
+            ("src", "Cargo extracted sources", format!("{}/.cargo/registry/src", HOME)),
+            ("git", "Cargo git checkouts", format!("{}/.cargo/git/checkouts", HOME)),
+        ])
+    }
+}
+
+/// npm's content cache, the pnpm store and the yarn cache.
+pub struct NodeCaches;
+impl CleanupProvider for NodeCaches {
+    fn id(&self) -> &'static str {
+        "node-cache"
+    }
+
+    fn name(&self) -> &'static str {
+        "npm / pnpm / yarn Caches"
+    }
+
+    fn risk(&self) -> CleanupRisk {
+        CleanupRisk::Low
+    }
+
+    fn method(&self) -> &'static str {
+        "npm cache clean --force, pnpm store prune, or delete the yarn cache"
+    }
+
+    fn candidates(&self) -> Vec<CleanupItem> {
+        let mut items = existing_dirs(self, &[
+            ("npm", "npm cache", format!("{}/.npm/_cacache", HOME)),
+            ("pnpm", "pnpm store", format!("{}/.local/share/pnpm/store", HOME)),
+            ("yarn", "yarn cache", format!("{}/.cache/yarn", HOME)),
+        ]);
+        for item in &mut items {
+            if item.id.ends_with("_pnpm") {
+                // Prune only drops packages no project references any more, so the
+                // full store size is an upper bound
+                item.method = "pnpm store prune (keeps packages still in use)".into();
+            }
+        }
+        items
+    }
+
+    fn targets(&self, item: &CleanupItem) -> Option<Vec<PathBuf>> {
+        match item.id.rsplit('_').next() {
+            Some("npm") if has_command("npm") => None,
+            Some("pnpm") => None,
+            _ => Some(vec![PathBuf::from(&item.path)]),
+        }
+    }
+
+    fn clean(&self, item: &CleanupItem) -> Result<()> {
+        match item.id.rsplit('_').next() {
+            Some("npm") => run_command("npm", &["cache", "clean", "--force"]),
+            Some("pnpm") if has_command("pnpm") => run_command("pnpm", &["store", "prune"]),
+            _ => Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "pnpm is not installed")),
+        }
+    }
+}
+
+/// pip's wheel and HTTP cache.
+pub struct PipCache;
+impl CleanupProvider for PipCache {
+    fn id(&self) -> &'static str {
+        "pip-cache"
+    }
+
+    fn name(&self) -> &'static str {
+        "pip Cache"
+    }
+
+    fn risk(&self) -> CleanupRisk {
+        CleanupRisk::Low
+    }
+
+    fn method(&self) -> &'static str {
+        "Delete ~/.cache/pip; pip downloads packages again when needed"
+    }
+
+    fn candidates(&self) -> Vec<CleanupItem> {
+        existing_dirs(self, &[("pip", "pip cache", format!("{}/.cache/pip", HOME))])
+    }
+}
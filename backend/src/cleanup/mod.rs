@@ -0,0 +1,327 @@
+pub mod dev;
+pub mod user;
+pub mod system;
+pub mod trash;
+pub mod policy;
+
+use std::collections::BTreeMap;
+use std::io::{Error, ErrorKind, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{CleanupItem, CleanupRisk};
+use walkdir::WalkDir;
+
+use crate::storage::{calculate_dir_size, delete_path, ensure_within, read_json};
+use policy::CleanupPolicy;
+use trash::{TrashConfig, TrashEntry};
+
+// --- Cleanup Providers ---
+//
+// Each provider knows one kind of reclaimable space: where to find it, how
+// risky removing it is, and how to remove it. Providers whose tooling or
+// directories are missing on this machine report themselves unavailable and
+// are skipped. Cleanup requests are only honoured for paths the provider
+// currently reports as candidates, and files are only removed or trashed
+// inside the canonicalized allowed roots.
+
+pub const HOME: &str = "/home/a2";
+pub const WORKSPACE: &str = "/home/a2/Desktop/gem";
+const CLEANUP_CONFIG_FILE: &str = "cleanup_config.json";
+const DEFAULT_PREVIEW_LIMIT: usize = 1000;
+
+fn default_min_size_mb() -> u64 {
+    100
+}
+
+fn default_policy_interval() -> u64 {
+    300
+}
+
+fn default_allowed_roots() -> Vec<String> {
+    [".gemini", ".cache", ".cargo/registry", ".cargo/git", ".npm", ".local/share/pnpm"].iter()
+        .map(|dir| format!("{}/{}", HOME, dir))
+        .chain([WORKSPACE.to_string()])
+        .collect()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CleanupMode {
+    #[default]
+    Delete,
+    Trash, // Opt-in: restorable, but frees nothing until the trash is purged
+    DryRun,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ProviderConfig {
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    #[serde(default)]
+    pub min_size_mb: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CleanupConfig {
+    #[serde(default = "default_min_size_mb")]
+    pub min_size_mb: u64, // Candidates reclaiming less are not listed
+    #[serde(default)]
+    pub providers: BTreeMap<String, ProviderConfig>,
+    #[serde(default = "default_allowed_roots")]
+    pub allowed_roots: Vec<String>, // Paths are removed or trashed only below these
+    #[serde(default)]
+    pub default_mode: CleanupMode, // Used when a request does not name a mode
+    #[serde(default)]
+    pub trash: TrashConfig,
+    #[serde(default)]
+    pub policies: Vec<CleanupPolicy>, // None by default; automatic deletion is opt-in
+    #[serde(default = "default_policy_interval")]
+    pub policy_interval_secs: u64,
+}
+
+impl Default for CleanupConfig {
+    fn default() -> Self {
+        Self {
+            min_size_mb: default_min_size_mb(),
+            providers: BTreeMap::new(),
+            allowed_roots: default_allowed_roots(),
+            default_mode: CleanupMode::default(),
+            trash: TrashConfig::default(),
+            policies: vec![],
+            policy_interval_secs: default_policy_interval(),
+        }
+    }
+}
+
+impl CleanupConfig {
+    pub fn load() -> Self {
+        read_json(CLEANUP_CONFIG_FILE).unwrap_or_default()
+    }
+
+    pub fn enabled(&self, id: &str) -> bool {
+        self.providers.get(id).and_then(|p| p.enabled).unwrap_or(true)
+    }
+
+    pub fn min_size(&self, id: &str) -> u64 {
+        self.providers.get(id).and_then(|p| p.min_size_mb).unwrap_or(self.min_size_mb) * 1024 * 1024
+    }
+}
+
+pub trait CleanupProvider {
+    /// Stable identifier, used as the `category` of every candidate.
+    fn id(&self) -> &'static str;
+    fn name(&self) -> &'static str;
+    fn risk(&self) -> CleanupRisk;
+    /// How `clean` frees the space, e.g. the command it runs.
+    fn method(&self) -> &'static str;
+    /// Whether the tooling or directories the provider needs exist here.
+    fn available(&self) -> bool {
+        true
+    }
+    fn candidates(&self) -> Vec<CleanupItem>;
+    /// Files or directories removing `item` deletes, or `None` when a command
+    /// frees the space through `clean` and the exact files are not known.
+    fn targets(&self, item: &CleanupItem) -> Option<Vec<PathBuf>> {
+        Some(vec![PathBuf::from(&item.path)])
+    }
+    /// Refuses cleanup while the item is in use.
+    fn ready(&self, _item: &CleanupItem) -> Result<()> {
+        Ok(())
+    }
+    /// Frees the space of items without `targets`.
+    fn clean(&self, _item: &CleanupItem) -> Result<()> {
+        Err(Error::new(ErrorKind::Unsupported, "No cleanup command"))
+    }
+}
+
+/// Every provider, in display order.
+pub fn providers() -> Vec<Box<dyn CleanupProvider>> {
+    vec![
+        Box::new(user::GeminiBrains),
+        Box::new(dev::BuildArtifacts),
+        Box::new(dev::CargoRegistry),
+        Box::new(dev::NodeCaches),
+        Box::new(dev::PipCache),
+        Box::new(user::BrowserCaches),
+        Box::new(user::UserCache),
+        Box::new(system::PackageCache),
+        Box::new(system::Journal),
+        Box::new(system::Podman),
+    ]
+}
+
+/// What one provider found, for `GET /api/disk/providers`.
+#[derive(Debug, Serialize)]
+pub struct ProviderReport {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub available: bool,
+    pub enabled: bool,
+    pub risk: CleanupRisk,
+    pub method: &'static str,
+    pub reclaim: u64,
+    pub candidates: Vec<CleanupItem>,
+}
+
+pub fn scan_providers(config: &CleanupConfig) -> Vec<ProviderReport> {
+    providers().into_iter().map(|p| {
+        let available = p.available();
+        let enabled = config.enabled(p.id());
+        let min_size = config.min_size(p.id());
+        let mut candidates: Vec<CleanupItem> = if available && enabled {
+            p.candidates().into_iter().filter(|c| c.reclaim >= min_size).collect()
+        } else {
+            vec![]
+        };
+        candidates.sort_by_key(|c| std::cmp::Reverse(c.reclaim));
+        ProviderReport {
+            id: p.id(),
+            name: p.name(),
+            available,
+            enabled,
+            risk: p.risk(),
+            method: p.method(),
+            reclaim: candidates.iter().map(|c| c.reclaim).sum(),
+            candidates,
+        }
+    }).collect()
+}
+
+#[derive(Debug, Serialize)]
+pub struct PreviewFile {
+    pub path: String,
+    pub size: u64,
+}
+
+/// What a cleanup would remove, without removing anything.
+#[derive(Debug, Serialize)]
+pub struct CleanupPreview {
+    pub exact: bool, // false when a command decides what goes; `bytes` is then the estimate
+    pub method: String,
+    pub file_count: usize,
+    pub bytes: u64,
+    pub files: Vec<PreviewFile>,
+    pub truncated: bool, // `files` was cut at the limit; the totals are still complete
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum CleanupOutcome {
+    Delete { bytes: u64 },
+    Trash { items: Vec<TrashEntry>, bytes: u64 }, // Bytes freed, always 0: trashed files stay on disk
+    DryRun { preview: CleanupPreview },
+}
+
+fn preview(item: &CleanupItem, targets: Option<&[PathBuf]>, limit: usize) -> CleanupPreview {
+    let Some(targets) = targets else {
+        return CleanupPreview { exact: false, method: item.method.clone(), file_count: 0, bytes: item.reclaim, files: vec![], truncated: false };
+    };
+    let mut preview = CleanupPreview { exact: true, method: item.method.clone(), file_count: 0, bytes: 0, files: vec![], truncated: false };
+    for entry in targets.iter().flat_map(WalkDir::new).filter_map(|e| e.ok()) {
+        let Ok(meta) = entry.metadata() else { continue };
+        if meta.is_dir() {
+            continue;
+        }
+        preview.file_count += 1;
+        preview.bytes += meta.len();
+        if preview.files.len() < limit {
+            preview.files.push(PreviewFile { path: entry.path().to_string_lossy().into(), size: meta.len() });
+        } else {
+            preview.truncated = true;
+        }
+    }
+    preview
+}
+
+/// Cleans `path` through the provider named by `category`, if it is one of its current candidates.
+pub fn perform(config: &CleanupConfig, path: &str, category: &str, mode: CleanupMode, limit: Option<usize>) -> Result<CleanupOutcome> {
+    let provider = providers().into_iter()
+        .find(|p| p.id() == category)
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "Unknown category"))?;
+    if !config.enabled(category) || !provider.available() {
+        return Err(Error::new(ErrorKind::Unsupported, format!("Provider {} is not available", category)));
+    }
+    let item = provider.candidates().into_iter()
+        .find(|c| c.path == path)
+        .ok_or_else(|| Error::new(ErrorKind::PermissionDenied, "Not a cleanup candidate"))?;
+    perform_item(config, provider.as_ref(), &item, mode, limit)
+}
+
+/// Cleans `item`, which the caller got from `provider.candidates()`.
+pub fn perform_item(config: &CleanupConfig, provider: &dyn CleanupProvider, item: &CleanupItem, mode: CleanupMode, limit: Option<usize>) -> Result<CleanupOutcome> {
+    let category = provider.id();
+    let targets = provider.targets(item);
+    if let Some(targets) = &targets {
+        for target in targets {
+            ensure_within(target, &config.allowed_roots)?;
+        }
+    }
+    if mode == CleanupMode::DryRun {
+        let preview = preview(item, targets.as_deref(), limit.unwrap_or(DEFAULT_PREVIEW_LIMIT));
+        return Ok(CleanupOutcome::DryRun { preview });
+    }
+
+    provider.ready(item)?;
+    match (mode, targets) {
+        (CleanupMode::Trash, Some(targets)) => {
+            let items = targets.iter()
+                .map(|t| trash::trash(&config.trash, t, category))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(CleanupOutcome::Trash { items, bytes: 0 })
+        }
+        (CleanupMode::Trash, None) => Err(Error::new(ErrorKind::Unsupported,
+            format!("{} is cleaned by a command ({}) and cannot be moved to the trash", item.name, item.method))),
+        (_, Some(targets)) => {
+            let mut bytes = 0;
+            for target in targets {
+                bytes += calculate_dir_size(&target.to_string_lossy());
+                delete_path(&target.to_string_lossy(), &config.allowed_roots)?;
+            }
+            Ok(CleanupOutcome::Delete { bytes })
+        }
+        (_, None) => {
+            provider.clean(item)?;
+            Ok(CleanupOutcome::Delete { bytes: item.reclaim })
+        }
+    }
+}
+
+// --- Helpers shared by providers ---
+
+/// A candidate with the provider's defaults and the full size as reclaim estimate.
+pub fn candidate(provider: &dyn CleanupProvider, key: &str, name: String, path: String, size: u64) -> CleanupItem {
+    CleanupItem {
+        id: format!("{}_{}", provider.id(), key),
+        name,
+        path,
+        size,
+        category: provider.id().into(),
+        reclaim: size,
+        risk: provider.risk(),
+        method: provider.method().into(),
+    }
+}
+
+/// Directory candidates for whichever of `dirs` (key, name, path) exist.
+pub fn existing_dirs(provider: &dyn CleanupProvider, dirs: &[(&str, &str, String)]) -> Vec<CleanupItem> {
+    dirs.iter()
+        .filter(|(_, _, path)| Path::new(path).is_dir())
+        .map(|(key, name, path)| candidate(provider, key, name.to_string(), path.clone(), calculate_dir_size(path)))
+        .collect()
+}
+
+pub fn has_command(name: &str) -> bool {
+    std::env::var_os("PATH").is_some_and(|paths| std::env::split_paths(&paths).any(|dir| dir.join(name).is_file()))
+}
+
+pub fn run_command(program: &str, args: &[&str]) -> Result<()> {
+    let status = Command::new(program).args(args).status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(Error::other(format!("{} {} failed", program, args.join(" "))))
+    }
+}
@@ -0,0 +1,404 @@
+use std::io::{Error, ErrorKind, Result};
+use std::path::Path;
+use std::process::Command;
+use std::sync::{LazyLock, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::cleanup::{has_command, perform_item, providers, run_command, CleanupConfig, CleanupMode};
+use crate::models::CleanupRisk;
+use crate::mounts::{fs_space, pct};
+use crate::scan_jobs::{self, ScanKind};
+use crate::storage::{atomic_write_json, calculate_dir_size, read_json, FileLock};
+
+// --- Automatic Cleanup Policies ---
+//
+// A policy fires when its mount is fuller than `trigger_pct` and runs its
+// steps in order, re-measuring after every step (and every item of a provider
+// step) until usage is below `target_pct`. The server scheduler evaluates
+// enabled policies every `policy_interval_secs`, but runs a policy at most
+// once per `min_interval_secs`. Dry runs execute nothing; they subtract each
+// step's estimated reclaim from the measured usage instead. Every run is
+// recorded with what each step reclaimed, measured as the change in free
+// space (so concurrent writes on the mount show up as noise).
+
+const POLICY_RUNS_FILE: &str = "cleanup_policy_runs.json";
+const MAX_RECORDED_RUNS: usize = 200;
+const JOURNAL_DIR: &str = "/var/log/journal/";
+/// Non-pacman package managers: (binary, cache dir, sudo arguments).
+/// `sudo -n` fails instead of waiting for a password nobody will type.
+const OTHER_PACKAGE_MANAGERS: &[(&str, &str, &[&str])] = &[
+    ("apt-get", "/var/cache/apt/archives/", &["-n", "apt-get", "clean"]),
+    ("dnf", "/var/cache/dnf/", &["-n", "dnf", "clean", "packages"]),
+];
+
+/// Held while a policy runs, so the scheduler and manual runs never overlap.
+static RUNNING: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_mount() -> String {
+    "/".into()
+}
+
+fn default_min_interval() -> u64 {
+    6 * 60 * 60
+}
+
+fn default_keep() -> u32 {
+    2
+}
+
+fn default_journal_mb() -> u64 {
+    500
+}
+
+fn default_step_mode() -> CleanupMode {
+    CleanupMode::Delete // Trash stays on the same filesystem and frees nothing
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum PolicyStep {
+    /// `paccache -rk<keep>` on pacman systems, the package manager's own clean elsewhere.
+    PackageCache {
+        #[serde(default = "default_keep")]
+        keep: u32,
+    },
+    /// `journalctl --vacuum-size=<max_size_mb>M`.
+    Journal {
+        #[serde(default = "default_journal_mb")]
+        max_size_mb: u64,
+    },
+    /// Candidates of a cleanup provider, largest first.
+    Provider {
+        provider: String,
+        #[serde(default)]
+        older_than_days: Option<u64>, // Only candidates whose mtime is older
+        #[serde(default)]
+        max_risk: CleanupRisk,
+        #[serde(default = "default_step_mode")]
+        mode: CleanupMode,
+    },
+}
+
+impl PolicyStep {
+    pub fn describe(&self) -> String {
+        match self {
+            PolicyStep::PackageCache { keep } => format!("Purge package cache keeping {} versions", keep),
+            PolicyStep::Journal { max_size_mb } => format!("Vacuum journal to {}MB", max_size_mb),
+            PolicyStep::Provider { provider, older_than_days: Some(days), .. } => format!("Clean {} older than {} days", provider, days),
+            PolicyStep::Provider { provider, .. } => format!("Clean {}", provider),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CleanupPolicy {
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_mount")]
+    pub mount: String, // Any path on the filesystem to watch
+    pub trigger_pct: f64,
+    #[serde(default)]
+    pub target_pct: Option<f64>, // Stop once usage is below; defaults to `trigger_pct`
+    #[serde(default = "default_min_interval")]
+    pub min_interval_secs: u64, // Scheduler runs at most this often
+    #[serde(default)]
+    pub dry_run: bool, // Scheduler only simulates this policy
+    pub steps: Vec<PolicyStep>,
+}
+
+impl CleanupPolicy {
+    pub fn target(&self) -> f64 {
+        self.target_pct.unwrap_or(self.trigger_pct)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Done,
+    Simulated,
+    Skipped, // Target already reached
+    Failed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StepResult {
+    pub step: String,
+    pub status: StepStatus,
+    pub reclaimed: u64, // Estimated for simulated steps
+    #[serde(default)]
+    pub items: Vec<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RunTrigger {
+    Scheduler,
+    Manual,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PolicyRun {
+    pub policy: String,
+    pub trigger: RunTrigger,
+    pub dry_run: bool,
+    pub started_at: u64, // Unix seconds
+    pub finished_at: u64,
+    pub usage_before: f64,
+    pub usage_after: f64, // Simulated for dry runs
+    pub target_pct: f64,
+    pub reached_target: bool,
+    pub reclaimed: u64,
+    pub steps: Vec<StepResult>,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+pub fn load_runs() -> Vec<PolicyRun> {
+    read_json(POLICY_RUNS_FILE).unwrap_or_default()
+}
+
+fn record_run(run: &PolicyRun) -> Result<()> {
+    let _lock = FileLock::new(POLICY_RUNS_FILE)?;
+    let mut runs = load_runs();
+    runs.push(run.clone());
+    let excess = runs.len().saturating_sub(MAX_RECORDED_RUNS);
+    runs.drain(..excess);
+    atomic_write_json(POLICY_RUNS_FILE, &runs)
+}
+
+/// Measured or simulated state of the policy's filesystem during a run.
+struct Usage {
+    total: u64,
+    used: u64,
+    simulated: bool,
+}
+
+impl Usage {
+    fn measure(mount: &str, simulated: bool) -> Result<Self> {
+        let (total, free) = fs_space(Path::new(mount))
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, format!("Cannot stat {}", mount)))?;
+        Ok(Self { total, used: total.saturating_sub(free), simulated })
+    }
+
+    fn pct(&self) -> f64 {
+        pct(self.used, self.total)
+    }
+
+    /// Applies `estimate` (dry run) or re-measures; returns the bytes freed.
+    fn update(&mut self, mount: &str, estimate: u64) -> u64 {
+        if self.simulated {
+            let freed = estimate.min(self.used);
+            self.used -= freed;
+            return freed;
+        }
+        let before = self.used;
+        if let Some((total, free)) = fs_space(Path::new(mount)) {
+            self.total = total;
+            self.used = total.saturating_sub(free);
+        }
+        before.saturating_sub(self.used)
+    }
+}
+
+/// Bytes `paccache -d` says a real run would free.
+fn paccache_estimate(keep: u32) -> u64 {
+    let Ok(output) = Command::new("paccache").args(["-d", &format!("-k{}", keep)]).output() else { return 0 };
+    let text = String::from_utf8_lossy(&output.stdout);
+    // "==> finished dry run: 12 candidates (disk space saved: 1.23 GiB)"
+    let Some(saved) = text.split("disk space saved: ").nth(1) else { return 0 };
+    let mut parts = saved.trim_end_matches([')', '\n']).split_whitespace();
+    let value: f64 = parts.next().and_then(|v| v.parse().ok()).unwrap_or(0.0);
+    let unit = match parts.next().unwrap_or("B").trim_end_matches(')') {
+        "KiB" => 1024.0,
+        "MiB" => 1024.0 * 1024.0,
+        "GiB" => 1024.0 * 1024.0 * 1024.0,
+        "TiB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => 1.0,
+    };
+    (value * unit) as u64
+}
+
+fn file_age_days(path: &str) -> Option<u64> {
+    let modified = std::fs::symlink_metadata(path).ok()?.modified().ok()?;
+    Some(SystemTime::now().duration_since(modified).unwrap_or_default().as_secs() / (24 * 60 * 60))
+}
+
+fn run_step(config: &CleanupConfig, policy: &CleanupPolicy, step: &PolicyStep, usage: &mut Usage) -> StepResult {
+    let mut result = StepResult {
+        step: step.describe(),
+        status: if usage.simulated { StepStatus::Simulated } else { StepStatus::Done },
+        reclaimed: 0,
+        items: vec![],
+        error: None,
+    };
+    let outcome = match step {
+        PolicyStep::PackageCache { keep } if has_command("pacman") => {
+            if !has_command("paccache") {
+                Err(Error::new(ErrorKind::Unsupported, "paccache (pacman-contrib) is not installed"))
+            } else if usage.simulated {
+                result.reclaimed = usage.update(&policy.mount, paccache_estimate(*keep));
+                Ok(())
+            } else {
+                let done = run_command("sudo", &["-n", "paccache", &format!("-rk{}", keep)]);
+                result.reclaimed = usage.update(&policy.mount, 0);
+                done
+            }
+        }
+        PolicyStep::PackageCache { .. } => match OTHER_PACKAGE_MANAGERS.iter().find(|(bin, _, _)| has_command(bin)) {
+            None => Err(Error::new(ErrorKind::Unsupported, "No supported package manager")),
+            Some((_, dir, _)) if usage.simulated => {
+                // These clean the whole cache; `keep` only applies to pacman
+                result.reclaimed = usage.update(&policy.mount, calculate_dir_size(dir));
+                result.items.push(dir.to_string());
+                Ok(())
+            }
+            Some((_, dir, args)) => {
+                let done = run_command("sudo", args);
+                result.reclaimed = usage.update(&policy.mount, 0);
+                result.items.push(dir.to_string());
+                done
+            }
+        },
+        PolicyStep::Journal { max_size_mb } => {
+            if !has_command("journalctl") {
+                Err(Error::new(ErrorKind::Unsupported, "journalctl is not installed"))
+            } else if usage.simulated {
+                let estimate = calculate_dir_size(JOURNAL_DIR).saturating_sub(max_size_mb * 1024 * 1024);
+                result.reclaimed = usage.update(&policy.mount, estimate);
+                Ok(())
+            } else {
+                let done = run_command("sudo", &["-n", "journalctl", &format!("--vacuum-size={}M", max_size_mb)]);
+                result.reclaimed = usage.update(&policy.mount, 0);
+                done
+            }
+        }
+        PolicyStep::Provider { .. } => run_provider(config, policy, step, usage, &mut result),
+    };
+    if let Err(e) = outcome {
+        result.status = StepStatus::Failed;
+        result.error = Some(e.to_string());
+    }
+    result
+}
+
+fn run_provider(config: &CleanupConfig, policy: &CleanupPolicy, step: &PolicyStep, usage: &mut Usage, result: &mut StepResult) -> Result<()> {
+    let PolicyStep::Provider { provider: id, older_than_days, max_risk, mode } = step else {
+        return Err(Error::new(ErrorKind::InvalidInput, "Not a provider step"));
+    };
+    let (id, older_than_days, max_risk, mode) = (id.as_str(), *older_than_days, *max_risk, *mode);
+    let provider = providers().into_iter()
+        .find(|p| p.id() == id)
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, format!("Unknown provider {}", id)))?;
+    if !config.enabled(id) || !provider.available() {
+        return Err(Error::new(ErrorKind::Unsupported, format!("Provider {} is not available", id)));
+    }
+    let mut candidates: Vec<_> = provider.candidates().into_iter()
+        .filter(|c| c.risk <= max_risk)
+        .filter(|c| older_than_days.is_none_or(|days| file_age_days(&c.path).is_some_and(|age| age >= days)))
+        .collect();
+    candidates.sort_by_key(|c| std::cmp::Reverse(c.reclaim));
+
+    for item in candidates {
+        if usage.pct() < policy.target() {
+            break;
+        }
+        if !usage.simulated {
+            perform_item(config, provider.as_ref(), &item, mode, None)
+                .map_err(|e| Error::new(e.kind(), format!("{}: {}", item.path, e)))?;
+        }
+        result.reclaimed += usage.update(&policy.mount, item.reclaim);
+        result.items.push(item.path);
+    }
+    Ok(())
+}
+
+/// Runs `policy` if its mount is over the trigger (always when `force`).
+/// Returns `None` when it did not fire.
+pub fn evaluate(config: &CleanupConfig, policy: &CleanupPolicy, trigger: RunTrigger, dry_run: bool, force: bool) -> Result<Option<PolicyRun>> {
+    let _running = RUNNING.try_lock()
+        .map_err(|_| Error::new(ErrorKind::ResourceBusy, "Another cleanup policy is running"))?;
+
+    let mut usage = Usage::measure(&policy.mount, dry_run)?;
+    let usage_before = usage.pct();
+    if !force && usage_before <= policy.trigger_pct {
+        return Ok(None);
+    }
+
+    let started_at = now_secs();
+    let mut steps = vec![];
+    for step in &policy.steps {
+        if usage.pct() < policy.target() {
+            steps.push(StepResult { step: step.describe(), status: StepStatus::Skipped, reclaimed: 0, items: vec![], error: None });
+            continue;
+        }
+        steps.push(run_step(config, policy, step, &mut usage));
+    }
+
+    let run = PolicyRun {
+        policy: policy.name.clone(),
+        trigger,
+        dry_run,
+        started_at,
+        finished_at: now_secs(),
+        usage_before,
+        usage_after: usage.pct(),
+        target_pct: policy.target(),
+        reached_target: usage.pct() < policy.target(),
+        reclaimed: steps.iter().map(|s| s.reclaimed).sum(),
+        steps,
+    };
+    record_run(&run)?;
+    if !dry_run && run.reclaimed > 0 {
+        scan_jobs::invalidate(ScanKind::Cleanup);
+        scan_jobs::invalidate(ScanKind::WorkspaceSize);
+    }
+    Ok(Some(run))
+}
+
+/// When the scheduler last ran `policy` (dry or not), from the run log.
+fn last_scheduled(runs: &[PolicyRun], policy: &str) -> Option<u64> {
+    runs.iter()
+        .filter(|r| r.policy == policy && r.trigger == RunTrigger::Scheduler)
+        .map(|r| r.started_at)
+        .max()
+}
+
+/// One scheduler pass over every enabled policy that is due.
+pub fn evaluate_due(config: &CleanupConfig) {
+    let runs = load_runs();
+    for policy in config.policies.iter().filter(|p| p.enabled) {
+        if last_scheduled(&runs, &policy.name).is_some_and(|t| now_secs().saturating_sub(t) < policy.min_interval_secs) {
+            continue;
+        }
+        match evaluate(config, policy, RunTrigger::Scheduler, policy.dry_run, false) {
+            Ok(Some(run)) => println!(
+                "🧹 Policy {}{}: {:.1}% -> {:.1}%, {} bytes reclaimed",
+                run.policy, if run.dry_run { " (dry run)" } else { "" }, run.usage_before, run.usage_after, run.reclaimed,
+            ),
+            Ok(None) => {}
+            Err(e) => eprintln!("⚠️ Cleanup policy {} failed: {}", policy.name, e),
+        }
+    }
+}
+
+/// Periodically evaluates the configured cleanup policies.
+pub async fn run_policy_scheduler() {
+    let interval_secs = CleanupConfig::load().policy_interval_secs.max(60);
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+    loop {
+        interval.tick().await;
+        let _ = tokio::task::spawn_blocking(|| evaluate_due(&CleanupConfig::load())).await;
+    }
+}
@@ -0,0 +1,175 @@
+use std::io::{Error, ErrorKind, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::cleanup::{candidate, has_command, run_command, CleanupProvider};
+use crate::models::{CleanupItem, CleanupRisk};
+use crate::storage::calculate_dir_size;
+use crate::system::{clean_pacman_cache, vacuum_system_journal};
+
+// --- System Package Managers & Containers ---
+
+/// Package managers this provider knows: (binary, cache dir, label).
+const PACKAGE_MANAGERS: &[(&str, &str, &str)] = &[
+    ("pacman", "/var/cache/pacman/pkg/", "Pacman package cache"),
+    ("apt-get", "/var/cache/apt/archives/", "APT package cache"),
+    ("dnf", "/var/cache/dnf/", "DNF package cache"),
+];
+const JOURNAL_DIR: &str = "/var/log/journal/";
+
+/// Downloaded packages of whichever package managers are installed.
+pub struct PackageCache;
+impl CleanupProvider for PackageCache {
+    fn id(&self) -> &'static str {
+        "package-cache"
+    }
+
+    fn name(&self) -> &'static str {
+        "System Package Cache"
+    }
+
+    fn risk(&self) -> CleanupRisk {
+        CleanupRisk::Low
+    }
+
+    fn method(&self) -> &'static str {
+        "Package manager's own cache cleanup via sudo"
+    }
+
+    fn available(&self) -> bool {
+        PACKAGE_MANAGERS.iter().any(|(bin, _, _)| has_command(bin))
+    }
+
+    fn candidates(&self) -> Vec<CleanupItem> {
+        PACKAGE_MANAGERS.iter()
+            .filter(|(bin, dir, _)| has_command(bin) && Path::new(dir).is_dir())
+            .map(|(bin, dir, label)| {
+                let mut item = candidate(self, bin, label.to_string(), dir.to_string(), calculate_dir_size(dir));
+                item.method = match *bin {
+                    "pacman" if has_command("paccache") => "sudo paccache -rk2 (keeps the last 2 versions)",
+                    "pacman" => "sudo pacman -Sc (keeps installed versions)",
+                    "apt-get" => "sudo apt-get clean",
+                    _ => "sudo dnf clean packages",
+                }.into();
+                item
+            })
+            .collect()
+    }
+
+    fn targets(&self, _item: &CleanupItem) -> Option<Vec<PathBuf>> {
+        None
+    }
+
+    fn clean(&self, item: &CleanupItem) -> Result<()> {
+        match item.path.as_str() {
+            "/var/cache/pacman/pkg/" if has_command("paccache") => run_command("sudo", &["paccache", "-rk2"]),
+            "/var/cache/pacman/pkg/" => clean_pacman_cache(),
+            "/var/cache/apt/archives/" => run_command("sudo", &["apt-get", "clean"]),
+            "/var/cache/dnf/" => run_command("sudo", &["dnf", "clean", "packages"]),
+            _ => Err(Error::new(ErrorKind::PermissionDenied, "Unsafe system path")),
+        }
+    }
+}
+
+/// Persistent systemd journal.
+pub struct Journal;
+impl CleanupProvider for Journal {
+    fn id(&self) -> &'static str {
+        "journal"
+    }
+
+    fn name(&self) -> &'static str {
+        "Systemd Journal Logs"
+    }
+
+    fn risk(&self) -> CleanupRisk {
+        CleanupRisk::Low
+    }
+
+    fn method(&self) -> &'static str {
+        "sudo journalctl --vacuum-time=7d (keeps the last week)"
+    }
+
+    fn available(&self) -> bool {
+        has_command("journalctl") && Path::new(JOURNAL_DIR).is_dir()
+    }
+
+    fn candidates(&self) -> Vec<CleanupItem> {
+        vec![candidate(self, "logs", self.name().into(), JOURNAL_DIR.into(), calculate_dir_size(JOURNAL_DIR))]
+    }
+
+    fn targets(&self, _item: &CleanupItem) -> Option<Vec<PathBuf>> {
+        None
+    }
+
+    fn clean(&self, _item: &CleanupItem) -> Result<()> {
+        vacuum_system_journal()
+    }
+}
+
+fn podman_json(args: &[&str]) -> Vec<serde_json::Value> {
+    Command::new("podman").args(args).output().ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| serde_json::from_slice(&o.stdout).ok())
+        .unwrap_or_default()
+}
+
+/// Dangling podman images and volumes no container uses.
+pub struct Podman;
+impl CleanupProvider for Podman {
+    fn id(&self) -> &'static str {
+        "podman"
+    }
+
+    fn name(&self) -> &'static str {
+        "Podman Images & Volumes"
+    }
+
+    fn risk(&self) -> CleanupRisk {
+        CleanupRisk::Low
+    }
+
+    fn method(&self) -> &'static str {
+        "podman image prune -f"
+    }
+
+    fn available(&self) -> bool {
+        has_command("podman")
+    }
+
+    fn candidates(&self) -> Vec<CleanupItem> {
+        let mut items = vec![];
+
+        let images = podman_json(&["images", "--filter", "dangling=true", "--format", "json"]);
+        let size: u64 = images.iter().filter_map(|i| i.get("Size").and_then(|s| s.as_u64())).sum();
+        if !images.is_empty() {
+            items.push(candidate(self, "images", format!("{} dangling images", images.len()), "podman:images".into(), size));
+        }
+
+        let volumes = podman_json(&["volume", "ls", "--filter", "dangling=true", "--format", "json"]);
+        let size: u64 = volumes.iter()
+            .filter_map(|v| v.get("Mountpoint").and_then(|m| m.as_str()))
+            .map(calculate_dir_size)
+            .sum();
+        if !volumes.is_empty() {
+            let mut item = candidate(self, "volumes", format!("{} unused volumes", volumes.len()), "podman:volumes".into(), size);
+            item.risk = CleanupRisk::High; // Volumes hold container data, not build layers
+            item.method = "podman volume prune -f".into();
+            items.push(item);
+        }
+
+        items
+    }
+
+    fn targets(&self, _item: &CleanupItem) -> Option<Vec<PathBuf>> {
+        None
+    }
+
+    fn clean(&self, item: &CleanupItem) -> Result<()> {
+        match item.path.as_str() {
+            "podman:images" => run_command("podman", &["image", "prune", "-f"]),
+            "podman:volumes" => run_command("podman", &["volume", "prune", "-f"]),
+            _ => Err(Error::new(ErrorKind::InvalidInput, "Unknown podman target")),
+        }
+    }
+}
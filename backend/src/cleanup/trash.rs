@@ -0,0 +1,277 @@
+use std::fs;
+use std::io::{Error, ErrorKind, Result};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::cleanup::{CleanupConfig, HOME};
+use crate::storage::{atomic_write_json, calculate_dir_size, read_json, FileLock};
+
+// --- Trash & Quarantine ---
+//
+// Trashed items go either to the XDG trash (with a `.trashinfo`, so file
+// managers can restore them too) or to a private quarantine directory. Both
+// are recorded in the quarantine manifest, which drives `POST /api/disk/restore`
+// and the scheduled purge of items older than the retention period.
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+fn default_quarantine_dir() -> String {
+    format!("{}/.local/share/mission-control/quarantine", HOME)
+}
+
+fn default_retention_days() -> u64 {
+    7
+}
+
+fn default_purge_interval() -> u64 {
+    3600
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TrashBackend {
+    #[default]
+    Xdg,
+    Quarantine,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrashConfig {
+    #[serde(default)]
+    pub backend: TrashBackend,
+    #[serde(default = "default_quarantine_dir")]
+    pub quarantine_dir: String, // Also holds the manifest for XDG-trashed items
+    #[serde(default = "default_retention_days")]
+    pub retention_days: u64,
+    #[serde(default = "default_purge_interval")]
+    pub purge_interval_secs: u64,
+}
+
+impl Default for TrashConfig {
+    fn default() -> Self {
+        Self {
+            backend: TrashBackend::default(),
+            quarantine_dir: default_quarantine_dir(),
+            retention_days: default_retention_days(),
+            purge_interval_secs: default_purge_interval(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrashEntry {
+    pub id: String,
+    pub original_path: String,
+    pub stored_path: String,
+    pub size: u64,
+    pub category: String,
+    pub backend: TrashBackend,
+    pub trashed_at: u64, // Unix seconds
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+fn manifest_path(config: &TrashConfig) -> String {
+    format!("{}/{}", config.quarantine_dir, MANIFEST_FILE)
+}
+
+fn xdg_trash() -> PathBuf {
+    let data_home = std::env::var("XDG_DATA_HOME").unwrap_or_else(|_| format!("{}/.local/share", HOME));
+    PathBuf::from(data_home).join("Trash")
+}
+
+/// Percent-encodes a path for the `Path=` key of a `.trashinfo` file.
+fn encode_path(path: &Path) -> String {
+    path.to_string_lossy().bytes().map(|b| match b {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => (b as char).to_string(),
+        _ => format!("%{:02X}", b),
+    }).collect()
+}
+
+fn copy_tree(from: &Path, to: &Path) -> Result<()> {
+    for entry in WalkDir::new(from) {
+        let entry = entry.map_err(Error::other)?;
+        let target = to.join(entry.path().strip_prefix(from).map_err(Error::other)?);
+        let file_type = entry.file_type();
+        if file_type.is_dir() {
+            fs::create_dir_all(&target)?;
+        } else if file_type.is_symlink() {
+            std::os::unix::fs::symlink(fs::read_link(entry.path())?, &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// `rename`, falling back to copy-and-delete across filesystems.
+fn move_path(from: &Path, to: &Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    match fs::rename(from, to) {
+        Err(e) if e.kind() == ErrorKind::CrossesDevices => {
+            copy_tree(from, to)?;
+            if from.is_dir() { fs::remove_dir_all(from) } else { fs::remove_file(from) }
+        }
+        other => other,
+    }
+}
+
+/// First `name`, `name.1`, `name.2`, ... that does not exist in `dir`.
+fn unique_name(dir: &Path, name: &str, also_free: impl Fn(&str) -> bool) -> String {
+    (0..).map(|i| if i == 0 { name.to_string() } else { format!("{}.{}", name, i) })
+        .find(|n| !dir.join(n).exists() && also_free(n))
+        .unwrap_or_else(|| name.to_string())
+}
+
+fn load_manifest(config: &TrashConfig) -> Vec<TrashEntry> {
+    read_json(&manifest_path(config)).unwrap_or_default()
+}
+
+fn update_manifest<T>(config: &TrashConfig, f: impl FnOnce(&mut Vec<TrashEntry>) -> Result<T>) -> Result<T> {
+    fs::create_dir_all(&config.quarantine_dir)?;
+    let path = manifest_path(config);
+    let _lock = FileLock::new(&path)?;
+    let mut entries = load_manifest(config);
+    let result = f(&mut entries)?;
+    atomic_write_json(&path, &entries)?;
+    Ok(result)
+}
+
+/// Moves `path` out of the way and records it for restore and purge.
+pub fn trash(config: &TrashConfig, path: &Path, category: &str) -> Result<TrashEntry> {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "Path has no file name"))?;
+    let size = if path.is_dir() { calculate_dir_size(&path.to_string_lossy()) } else { fs::metadata(path)?.len() };
+    let trashed_at = now_secs();
+    let id = format!("{}-{}", SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis(), name);
+
+    update_manifest(config, |entries| {
+        let stored = match config.backend {
+            TrashBackend::Xdg => {
+                let trash = xdg_trash();
+                let info_dir = trash.join("info");
+                let stored_name = unique_name(&trash.join("files"), &name, |n| !info_dir.join(format!("{}.trashinfo", n)).exists());
+                fs::create_dir_all(&info_dir)?;
+                let info = format!("[Trash Info]\nPath={}\nDeletionDate={}\n",
+                    encode_path(path), Local::now().format("%Y-%m-%dT%H:%M:%S"));
+                fs::write(info_dir.join(format!("{}.trashinfo", stored_name)), info)?;
+                trash.join("files").join(stored_name)
+            }
+            TrashBackend::Quarantine => Path::new(&config.quarantine_dir).join(&id).join(&name),
+        };
+        move_path(path, &stored)?;
+
+        let entry = TrashEntry {
+            id: id.clone(),
+            original_path: path.to_string_lossy().into(),
+            stored_path: stored.to_string_lossy().into(),
+            size,
+            category: category.into(),
+            backend: config.backend,
+            trashed_at,
+        };
+        entries.push(entry.clone());
+        Ok(entry)
+    })
+}
+
+fn trashinfo_for(entry: &TrashEntry) -> Option<PathBuf> {
+    let stored = Path::new(&entry.stored_path);
+    (entry.backend == TrashBackend::Xdg).then(|| {
+        let name = stored.file_name().unwrap_or_default().to_string_lossy().to_string();
+        stored.parent().and_then(|files| files.parent()).unwrap_or(stored)
+            .join("info").join(format!("{}.trashinfo", name))
+    })
+}
+
+/// Removes the stored copy and any bookkeeping next to it.
+fn discard(entry: &TrashEntry) -> Result<()> {
+    let stored = Path::new(&entry.stored_path);
+    if stored.is_dir() {
+        fs::remove_dir_all(stored)?;
+    } else if stored.symlink_metadata().is_ok() {
+        fs::remove_file(stored)?;
+    }
+    if let Some(info) = trashinfo_for(entry) {
+        let _ = fs::remove_file(info);
+    }
+    if entry.backend == TrashBackend::Quarantine
+        && let Some(dir) = stored.parent()
+    {
+        let _ = fs::remove_dir(dir);
+    }
+    Ok(())
+}
+
+pub fn list(config: &TrashConfig) -> Vec<TrashEntry> {
+    load_manifest(config)
+}
+
+/// Moves a trashed item back to where it came from.
+pub fn restore(config: &TrashConfig, id: &str) -> Result<TrashEntry> {
+    update_manifest(config, |entries| {
+        let index = entries.iter().position(|e| e.id == id)
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, "No trashed item with that id"))?;
+        let entry = entries[index].clone();
+        let original = Path::new(&entry.original_path);
+        if original.symlink_metadata().is_ok() {
+            return Err(Error::new(ErrorKind::AlreadyExists, format!("{} exists again; move it away first", entry.original_path)));
+        }
+        let stored = Path::new(&entry.stored_path);
+        if stored.symlink_metadata().is_err() {
+            entries.remove(index);
+            return Err(Error::new(ErrorKind::NotFound, "The trashed copy is gone (emptied from the trash?)"));
+        }
+        move_path(stored, original)?;
+        discard(&entry)?; // Leftover .trashinfo / quarantine dir
+        entries.remove(index);
+        Ok(entry)
+    })
+}
+
+/// Permanently deletes items trashed more than `retention_days` ago; returns (items, bytes).
+pub fn purge_expired(config: &TrashConfig) -> Result<(usize, u64)> {
+    let cutoff = now_secs().saturating_sub(config.retention_days * 24 * 60 * 60);
+    update_manifest(config, |entries| {
+        let (expired, kept): (Vec<TrashEntry>, Vec<TrashEntry>) = entries.drain(..).partition(|e| e.trashed_at < cutoff);
+        *entries = kept;
+        let mut purged = (0, 0);
+        for entry in expired {
+            match discard(&entry) {
+                Ok(()) => {
+                    purged.0 += 1;
+                    purged.1 += entry.size;
+                }
+                Err(e) => {
+                    eprintln!("⚠️ Could not purge {}: {}", entry.stored_path, e);
+                    entries.push(entry);
+                }
+            }
+        }
+        Ok(purged)
+    })
+}
+
+/// Periodically purges expired trash and quarantine items.
+pub async fn run_purge_scheduler() {
+    let interval_secs = CleanupConfig::load().trash.purge_interval_secs.max(60);
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+    loop {
+        interval.tick().await;
+        let _ = tokio::task::spawn_blocking(|| {
+            match purge_expired(&CleanupConfig::load().trash) {
+                Ok((0, _)) => {}
+                Ok((count, bytes)) => println!("🗑️ Purged {} expired trash items ({} bytes)", count, bytes),
+                Err(e) => eprintln!("⚠️ Trash purge failed: {}", e),
+            }
+        }).await;
+    }
+}
@@ -0,0 +1,163 @@
+use std::io::{Error, ErrorKind, Result};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use walkdir::WalkDir;
+
+use crate::cleanup::{candidate, CleanupProvider, HOME, WORKSPACE};
+use crate::models::{CleanupItem, CleanupRisk};
+use crate::storage::calculate_dir_size;
+
+// --- Per-user Data ---
+
+const BRAIN_MIN_AGE_DAYS: u64 = 7;
+/// Chromium cache directories, relative to the user-data dir or its `Default` profile.
+const BROWSER_CACHE_DIRS: &[&str] = &["Cache", "Code Cache", "GPUCache", "ShaderCache", "GrShaderCache", "DawnCache", "Service Worker/CacheStorage"];
+/// `~/.cache` entries that have a dedicated provider.
+const COVERED_CACHES: &[&str] = &["pip", "yarn", "pnpm"];
+
+/// Gemini agent brain directories untouched for a week.
+pub struct GeminiBrains;
+impl CleanupProvider for GeminiBrains {
+    fn id(&self) -> &'static str {
+        "gemini"
+    }
+
+    fn name(&self) -> &'static str {
+        "Gemini Agent Brains"
+    }
+
+    fn risk(&self) -> CleanupRisk {
+        CleanupRisk::Medium
+    }
+
+    fn method(&self) -> &'static str {
+        "Delete the brain directory; the agent loses that conversation's memory"
+    }
+
+    fn candidates(&self) -> Vec<CleanupItem> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let brain_path = format!("{}/.gemini/antigravity/brain", HOME);
+        WalkDir::new(&brain_path).min_depth(1).max_depth(1).into_iter()
+            .filter_map(|e| e.ok())
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+                if now.saturating_sub(modified) <= BRAIN_MIN_AGE_DAYS * 24 * 60 * 60 {
+                    return None;
+                }
+                let size = if metadata.is_file() { metadata.len() } else { calculate_dir_size(&entry.path().to_string_lossy()) };
+                let name = entry.file_name().to_string_lossy().to_string();
+                Some(candidate(self, &name, name.clone(), entry.path().to_string_lossy().into(), size))
+            })
+            .collect()
+    }
+}
+
+fn isolated_data_dirs() -> Vec<PathBuf> {
+    WalkDir::new(WORKSPACE).max_depth(4).into_iter()
+        .filter_entry(|e| !matches!(e.file_name().to_str(), Some(".git" | "node_modules" | "target" | ".venv")))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_dir() && e.file_name() == "isolated_data")
+        .map(|e| e.into_path())
+        .collect()
+}
+
+fn browser_cache_dirs(profile: &Path) -> Vec<PathBuf> {
+    ["", "Default"].iter()
+        .flat_map(|sub| BROWSER_CACHE_DIRS.iter().map(move |dir| profile.join(sub).join(dir)))
+        .filter(|p| p.is_dir())
+        .collect()
+}
+
+/// Chromium holds this symlink in the user-data dir while the profile is open.
+fn profile_in_use(profile: &Path) -> bool {
+    profile.join("SingletonLock").symlink_metadata().is_ok()
+}
+
+/// HTTP, code and GPU caches of the bridge's per-agent Chromium profiles.
+pub struct BrowserCaches;
+impl CleanupProvider for BrowserCaches {
+    fn id(&self) -> &'static str {
+        "browser-cache"
+    }
+
+    fn name(&self) -> &'static str {
+        "Agent Browser Caches (isolated_data)"
+    }
+
+    fn risk(&self) -> CleanupRisk {
+        CleanupRisk::Low
+    }
+
+    fn method(&self) -> &'static str {
+        "Delete the profile's cache directories only; logins and cookies are kept"
+    }
+
+    fn candidates(&self) -> Vec<CleanupItem> {
+        let mut items = vec![];
+        for root in isolated_data_dirs() {
+            let Ok(profiles) = std::fs::read_dir(&root) else { continue };
+            for profile in profiles.flatten().filter(|p| p.path().is_dir()) {
+                let caches = browser_cache_dirs(&profile.path());
+                let size: u64 = caches.iter().map(|c| calculate_dir_size(&c.to_string_lossy())).sum();
+                if caches.is_empty() {
+                    continue;
+                }
+                let agent = profile.file_name().to_string_lossy().to_string();
+                let mut item = candidate(self, &agent, format!("{} browser cache", agent), profile.path().to_string_lossy().into(), size);
+                if profile_in_use(&profile.path()) {
+                    item.risk = CleanupRisk::Medium;
+                    item.method = format!("{} (close the browser first)", item.method);
+                }
+                items.push(item);
+            }
+        }
+        items
+    }
+
+    fn targets(&self, item: &CleanupItem) -> Option<Vec<PathBuf>> {
+        Some(browser_cache_dirs(Path::new(&item.path)))
+    }
+
+    fn ready(&self, item: &CleanupItem) -> Result<()> {
+        if profile_in_use(Path::new(&item.path)) {
+            return Err(Error::new(ErrorKind::ResourceBusy, "Browser profile is in use"));
+        }
+        Ok(())
+    }
+}
+
+/// Everything else in `~/.cache`, one candidate per application.
+pub struct UserCache;
+impl CleanupProvider for UserCache {
+    fn id(&self) -> &'static str {
+        "user-cache"
+    }
+
+    fn name(&self) -> &'static str {
+        "Application Caches (~/.cache)"
+    }
+
+    fn risk(&self) -> CleanupRisk {
+        CleanupRisk::Medium
+    }
+
+    fn method(&self) -> &'static str {
+        "Delete the application's cache directory; some apps keep state there"
+    }
+
+    fn candidates(&self) -> Vec<CleanupItem> {
+        let Ok(entries) = std::fs::read_dir(format!("{}/.cache", HOME)) else { return vec![] };
+        entries.flatten()
+            .filter(|e| e.path().is_dir())
+            .filter(|e| !e.file_name().to_str().is_some_and(|n| COVERED_CACHES.contains(&n)))
+            .map(|e| {
+                let name = e.file_name().to_string_lossy().to_string();
+                let path = e.path().to_string_lossy().to_string();
+                let size = calculate_dir_size(&path);
+                candidate(self, &name, format!("~/.cache/{}", name), path, size)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::scan_jobs::{self, ScanJob, ScanKind};
+
+// --- Disk Usage Tree ---
+//
+// An ncdu-style view of directory sizes. A scan walks one subtree in the
+// background (staying on its filesystem, not following symlinks) and caches a
+// summary of every directory: totals plus its largest children. Requests are
+// served from the cache; a directory whose mtime changed since it was scanned
+// (entries added, removed or renamed) is rescanned and the totals of its
+// cached ancestors are adjusted. Scans run as `scan_jobs` jobs, so they show
+// progress and can be cancelled; a cancelled scan leaves the cache untouched.
+
+pub const DEFAULT_ROOT: &str = "/home/a2";
+pub const MAX_DEPTH: usize = 5;
+const MAX_CHILDREN: usize = 50;
+
+static CACHE: LazyLock<RwLock<TreeCache>> = LazyLock::new(|| RwLock::new(TreeCache::default()));
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TreeChild {
+    pub name: String,
+    pub path: String,
+    pub is_dir: bool,
+    pub size: u64, // Apparent size
+    pub disk_usage: u64, // Allocated blocks
+    pub files: u64,
+}
+
+#[derive(Debug, Clone)]
+struct DirSummary {
+    size: u64,
+    disk_usage: u64,
+    files: u64,
+    dirs: u64,
+    mtime_ns: i128,
+    children: Vec<TreeChild>, // Largest first, at most MAX_CHILDREN
+    other_children: usize, // Entries not listed in `children`
+    scanned_at: u64,
+}
+
+#[derive(Default)]
+struct TreeCache {
+    dirs: HashMap<PathBuf, DirSummary>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TreeNode {
+    pub path: String,
+    pub size: u64,
+    pub disk_usage: u64,
+    pub files: u64,
+    pub dirs: u64,
+    pub scanned_at: u64,
+    pub stale: bool, // Changed since the scan; a rescan is under way
+    pub other_children: usize,
+    pub children: Vec<TreeChildNode>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TreeChildNode {
+    #[serde(flatten)]
+    pub entry: TreeChild,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tree: Option<TreeNode>, // Expanded while `depth` allows and the child is cached
+}
+
+pub enum TreeLookup {
+    Ready(TreeNode),
+    Scanning(ScanJob),
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+fn mtime_ns(meta: &fs::Metadata) -> i128 {
+    meta.mtime() as i128 * 1_000_000_000 + meta.mtime_nsec() as i128
+}
+
+/// Post-order walk of `dir`, recording every directory summary into `out`.
+fn scan_dir(dir: &Path, device: u64, out: &mut Vec<(PathBuf, DirSummary)>) -> Option<DirSummary> {
+    let meta = fs::symlink_metadata(dir).ok()?;
+    let mut summary = DirSummary {
+        size: 0,
+        disk_usage: 0,
+        files: 0,
+        dirs: 0,
+        mtime_ns: mtime_ns(&meta),
+        children: vec![],
+        other_children: 0,
+        scanned_at: now_secs(),
+    };
+    let mut children = vec![];
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            if scan_jobs::cancelled() {
+                return None;
+            }
+            let Ok(meta) = entry.metadata() else { continue }; // does not follow symlinks
+            let path = entry.path();
+            let child = if meta.is_dir() && meta.dev() == device {
+                let Some(sub) = scan_dir(&path, device, out) else { continue };
+                summary.dirs += 1 + sub.dirs;
+                TreeChild { name: entry.file_name().to_string_lossy().into(), path: path.to_string_lossy().into(), is_dir: true, size: sub.size, disk_usage: sub.disk_usage, files: sub.files }
+            } else if meta.is_dir() {
+                continue; // Another filesystem is mounted here
+            } else {
+                scan_jobs::record(1, meta.len());
+                TreeChild { name: entry.file_name().to_string_lossy().into(), path: path.to_string_lossy().into(), is_dir: false, size: meta.len(), disk_usage: meta.blocks() * 512, files: 1 }
+            };
+            summary.size += child.size;
+            summary.disk_usage += child.disk_usage;
+            summary.files += child.files;
+            children.push(child);
+        }
+    }
+    children.sort_by_key(|c| std::cmp::Reverse(c.disk_usage));
+    summary.other_children = children.len().saturating_sub(MAX_CHILDREN);
+    children.truncate(MAX_CHILDREN);
+    summary.children = children;
+    out.push((dir.to_path_buf(), summary.clone()));
+    Some(summary)
+}
+
+/// Scans `root` and merges the result into the cache, correcting cached ancestors.
+pub fn scan(root: &Path) {
+    let Ok(meta) = fs::symlink_metadata(root) else { return };
+    let mut dirs = vec![];
+    let Some(summary) = scan_dir(root, meta.dev(), &mut dirs) else { return };
+
+    let mut cache = CACHE.write().unwrap_or_else(|e| e.into_inner());
+    let old = cache.dirs.get(root).map(|s| (s.size as i128, s.disk_usage as i128, s.files as i128));
+    cache.dirs.retain(|path, _| !path.starts_with(root));
+    cache.dirs.extend(dirs);
+
+    // Ancestors cached by an earlier, wider scan still hold the old totals
+    let (old_size, old_usage, old_files) = old.unwrap_or_default();
+    let (d_size, d_usage, d_files) = (summary.size as i128 - old_size, summary.disk_usage as i128 - old_usage, summary.files as i128 - old_files);
+    let mut child = root.to_path_buf();
+    while let Some(parent) = child.parent().map(Path::to_path_buf) {
+        let Some(p) = cache.dirs.get_mut(&parent) else { break };
+        p.size = (p.size as i128 + d_size).max(0) as u64;
+        p.disk_usage = (p.disk_usage as i128 + d_usage).max(0) as u64;
+        p.files = (p.files as i128 + d_files).max(0) as u64;
+        if let Some(entry) = p.children.iter_mut().find(|c| Path::new(&c.path) == child) {
+            entry.size = (entry.size as i128 + d_size).max(0) as u64;
+            entry.disk_usage = (entry.disk_usage as i128 + d_usage).max(0) as u64;
+            entry.files = (entry.files as i128 + d_files).max(0) as u64;
+        }
+        p.children.sort_by_key(|c| std::cmp::Reverse(c.disk_usage));
+        child = parent;
+    }
+}
+
+/// Starts a background scan of `path` unless one is already running.
+fn request_scan(path: &Path) -> ScanJob {
+    scan_jobs::start(ScanKind::DiskTree { path: path.to_string_lossy().into() })
+}
+
+fn is_stale(path: &Path, summary: &DirSummary) -> bool {
+    fs::symlink_metadata(path).map(|m| mtime_ns(&m) != summary.mtime_ns).unwrap_or(true)
+}
+
+fn build_node(cache: &TreeCache, path: &Path, depth: usize, stale_dirs: &mut Vec<PathBuf>) -> Option<TreeNode> {
+    let summary = cache.dirs.get(path)?;
+    let stale = is_stale(path, summary);
+    if stale {
+        stale_dirs.push(path.to_path_buf());
+    }
+    let children = summary.children.iter().map(|c| TreeChildNode {
+        entry: c.clone(),
+        tree: (c.is_dir && depth > 1).then(|| build_node(cache, Path::new(&c.path), depth - 1, stale_dirs)).flatten(),
+    }).collect();
+    Some(TreeNode {
+        path: path.to_string_lossy().into(),
+        size: summary.size,
+        disk_usage: summary.disk_usage,
+        files: summary.files,
+        dirs: summary.dirs,
+        scanned_at: summary.scanned_at,
+        stale,
+        other_children: summary.other_children,
+        children,
+    })
+}
+
+/// The cached tree below `path`, `depth` levels deep. Uncached or changed
+/// directories are (re)scanned in the background.
+pub fn lookup(path: &Path, depth: usize) -> TreeLookup {
+    let mut stale_dirs = vec![];
+    let node = {
+        let cache = CACHE.read().unwrap_or_else(|e| e.into_inner());
+        build_node(&cache, path, depth.clamp(1, MAX_DEPTH), &mut stale_dirs)
+    };
+    match node {
+        Some(node) => {
+            // Rescan only the outermost changed directories
+            stale_dirs.sort();
+            let mut rescanned: Vec<PathBuf> = vec![];
+            for dir in stale_dirs {
+                if !rescanned.iter().any(|r| dir.starts_with(r)) {
+                    request_scan(&dir);
+                    rescanned.push(dir);
+                }
+            }
+            TreeLookup::Ready(node)
+        }
+        None => TreeLookup::Scanning(request_scan(path)),
+    }
+}
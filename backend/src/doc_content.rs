@@ -0,0 +1,269 @@
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Read, Result, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use image::ImageFormat;
+
+// --- Doc Content Helpers ---
+//
+// Used by `/api/docs/content` to serve arbitrary artifacts (logs, PDFs,
+// screenshots) without loading them fully into memory.
+
+const SNIFF_LEN: usize = 8192;
+const TAIL_CHUNK: u64 = 64 * 1024;
+const MAX_EXCERPT: u64 = 1024 * 1024; // Head/tail reads stop here, however long the lines are
+const THUMBS_DIR: &str = "doc_store/thumbs";
+const MAX_CACHED_THUMBS: usize = 500;
+const MAX_THUMB_AGE_DAYS: u64 = 30;
+pub const MAX_THUMB_SIZE: u32 = 1024;
+
+/// Determines a Content-Type from magic bytes, then the extension, then a
+/// UTF-8 check on the first few KB.
+pub fn sniff_mime(path: &Path) -> Result<String> {
+    let mut prefix = Vec::with_capacity(SNIFF_LEN);
+    File::open(path)?.take(SNIFF_LEN as u64).read_to_end(&mut prefix)?;
+
+    if let Some(kind) = infer::get(&prefix) {
+        return Ok(kind.mime_type().to_string());
+    }
+
+    if let Some(guess) = mime_guess::from_path(path).first() {
+        let mime = guess.essence_str().to_string();
+        if guess.type_() == mime_guess::mime::TEXT || mime == "application/json" {
+            return Ok(format!("{}; charset=utf-8", mime));
+        }
+        if mime != "application/octet-stream" {
+            return Ok(mime);
+        }
+    }
+
+    if looks_like_text(&prefix) {
+        Ok("text/plain; charset=utf-8".into())
+    } else {
+        Ok("application/octet-stream".into())
+    }
+}
+
+fn looks_like_text(prefix: &[u8]) -> bool {
+    if prefix.contains(&0) {
+        return false;
+    }
+    match std::str::from_utf8(prefix) {
+        Ok(_) => true,
+        // A multi-byte character cut off at the end of the sniff window is fine
+        Err(e) => e.error_len().is_none(),
+    }
+}
+
+pub fn is_text(mime: &str) -> bool {
+    mime.starts_with("text/") || mime.contains("charset=utf-8")
+}
+
+pub enum ByteRange {
+    Full,
+    Partial(u64, u64), // inclusive start..=end
+    Unsatisfiable,
+}
+
+/// Parses a single-range `Range: bytes=...` header against a file of `size` bytes.
+/// Absent headers, non-byte units and multi-range requests are served in full.
+pub fn parse_range(header: Option<&str>, size: u64) -> ByteRange {
+    let Some(spec) = header.and_then(|h| h.trim().strip_prefix("bytes=")) else {
+        return ByteRange::Full;
+    };
+    if spec.contains(',') {
+        return ByteRange::Full;
+    }
+    let Some((start, end)) = spec.split_once('-') else {
+        return ByteRange::Unsatisfiable;
+    };
+
+    let last = size.saturating_sub(1);
+    let bounds = match (start.trim(), end.trim()) {
+        ("", suffix) => suffix.parse::<u64>().ok()
+            .filter(|n| *n > 0)
+            .map(|n| (size.saturating_sub(n), last)),
+        (s, "") => s.parse().ok().map(|s| (s, last)),
+        (s, e) => s.parse().ok().zip(e.parse::<u64>().ok()).map(|(s, e)| (s, e.min(last))),
+    };
+
+    match bounds {
+        Some((start, end)) if size > 0 && start <= end && start < size => ByteRange::Partial(start, end),
+        _ => ByteRange::Unsatisfiable,
+    }
+}
+
+/// Returns the first `n` lines of a text file, or its first `MAX_EXCERPT`
+/// bytes if those lines are longer.
+pub fn read_head(path: &Path, n: usize) -> Result<String> {
+    let reader = BufReader::new(File::open(path)?.take(MAX_EXCERPT));
+    let mut out = String::new();
+    for line in reader.split(b'\n').take(n) {
+        out.push_str(&String::from_utf8_lossy(&line?));
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Returns the last `n` lines of a text file, reading backwards in chunks so
+/// only the tail is ever loaded (at most `MAX_EXCERPT` bytes of it).
+pub fn read_tail(path: &Path, n: usize) -> Result<String> {
+    if n == 0 {
+        return Ok(String::new());
+    }
+    let mut file = File::open(path)?;
+    let size = file.metadata()?.len();
+
+    let mut pos = size;
+    let mut buf: Vec<u8> = vec![];
+    while pos > 0 && (buf.len() as u64) < MAX_EXCERPT {
+        let step = TAIL_CHUNK.min(pos).min(MAX_EXCERPT - buf.len() as u64);
+        pos -= step;
+        file.seek(SeekFrom::Start(pos))?;
+        let mut chunk = vec![0u8; step as usize];
+        file.read_exact(&mut chunk)?;
+        chunk.extend_from_slice(&buf);
+        buf = chunk;
+
+        // One extra newline is needed when the file ends with one
+        if buf.iter().filter(|&&b| b == b'\n').count() > n {
+            break;
+        }
+    }
+
+    let text = String::from_utf8_lossy(&buf);
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.is_empty() {
+        return Ok(String::new());
+    }
+    let mut out = lines[lines.len().saturating_sub(n)..].join("\n");
+    out.push('\n');
+    Ok(out)
+}
+
+pub fn is_thumbnailable(mime: &str) -> bool {
+    ImageFormat::from_mime_type(mime).is_some_and(|f| f.reading_enabled())
+}
+
+/// Renders (or reuses a cached) PNG thumbnail no larger than `size` px on either side.
+/// Cache entries are keyed by path, mtime, file length and thumbnail size, so
+/// edits invalidate them even within the same second.
+pub fn thumbnail(path: &Path, size: u32) -> Result<Vec<u8>> {
+    let size = size.clamp(16, MAX_THUMB_SIZE);
+    let meta = fs::metadata(path)?;
+    let mtime = meta.modified()?
+        .duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs();
+
+    let key = blake3::hash(format!("{}:{}:{}:{}", path.display(), mtime, meta.len(), size).as_bytes()).to_hex();
+    let cached = PathBuf::from(THUMBS_DIR).join(format!("{}.png", key));
+    if let Ok(bytes) = fs::read(&cached) {
+        return Ok(bytes);
+    }
+
+    let img = image::ImageReader::open(path)?
+        .with_guessed_format()?
+        .decode()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+    let mut out = std::io::Cursor::new(vec![]);
+    img.thumbnail(size, size)
+        .write_to(&mut out, ImageFormat::Png)
+        .map_err(std::io::Error::other)?;
+    let bytes = out.into_inner();
+
+    let _ = fs::create_dir_all(THUMBS_DIR);
+    let _ = fs::write(&cached, &bytes);
+    prune_thumbs(Path::new(THUMBS_DIR));
+    Ok(bytes)
+}
+
+/// Drops cached thumbnails older than `MAX_THUMB_AGE_DAYS`, then the oldest
+/// ones beyond `MAX_CACHED_THUMBS`. Stale entries are never read again once
+/// their source changes, so without this the cache only grows.
+fn prune_thumbs(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    let max_age = std::time::Duration::from_secs(MAX_THUMB_AGE_DAYS * 24 * 60 * 60);
+    let mut thumbs: Vec<(std::time::SystemTime, PathBuf)> = vec![];
+    for entry in entries.flatten() {
+        let Ok(modified) = entry.metadata().and_then(|m| m.modified()) else { continue };
+        if modified.elapsed().unwrap_or_default() > max_age {
+            let _ = fs::remove_file(entry.path());
+        } else {
+            thumbs.push((modified, entry.path()));
+        }
+    }
+    if thumbs.len() > MAX_CACHED_THUMBS {
+        thumbs.sort();
+        for (_, path) in &thumbs[..thumbs.len() - MAX_CACHED_THUMBS] {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn partial(header: &str, size: u64) -> Option<(u64, u64)> {
+        match parse_range(Some(header), size) {
+            ByteRange::Partial(start, end) => Some((start, end)),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn parse_range_serves_missing_and_multi_ranges_in_full() {
+        assert!(matches!(parse_range(None, 100), ByteRange::Full));
+        assert!(matches!(parse_range(Some("items=0-5"), 100), ByteRange::Full));
+        assert!(matches!(parse_range(Some("bytes=0-5,10-20"), 100), ByteRange::Full));
+    }
+
+    #[test]
+    fn parse_range_handles_bounded_open_and_suffix_ranges() {
+        assert_eq!(partial("bytes=0-9", 100), Some((0, 9)));
+        assert_eq!(partial("bytes=90-", 100), Some((90, 99)));
+        assert_eq!(partial("bytes=-10", 100), Some((90, 99)));
+        assert_eq!(partial("bytes=-500", 100), Some((0, 99)));
+        assert_eq!(partial("bytes=50-500", 100), Some((50, 99)));
+    }
+
+    #[test]
+    fn parse_range_rejects_unsatisfiable_ranges() {
+        for header in ["bytes=100-", "bytes=9-3", "bytes=-0", "bytes=abc", "bytes=a-b"] {
+            assert!(matches!(parse_range(Some(header), 100), ByteRange::Unsatisfiable), "{}", header);
+        }
+        assert!(matches!(parse_range(Some("bytes=0-0"), 0), ByteRange::Unsatisfiable));
+    }
+
+    #[test]
+    fn read_tail_returns_the_last_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log.txt");
+        fs::write(&path, "one\ntwo\nthree\n").unwrap();
+        assert_eq!(read_tail(&path, 2).unwrap(), "two\nthree\n");
+        assert_eq!(read_tail(&path, 10).unwrap(), "one\ntwo\nthree\n");
+        assert_eq!(read_tail(&path, 0).unwrap(), "");
+
+        fs::write(&path, "").unwrap();
+        assert_eq!(read_tail(&path, 3).unwrap(), "");
+    }
+
+    #[test]
+    fn prune_thumbs_keeps_the_newest_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = std::time::SystemTime::now();
+        for i in 0..MAX_CACHED_THUMBS + 2 {
+            let file = File::create(dir.path().join(format!("{}.png", i))).unwrap();
+            file.set_modified(now - std::time::Duration::from_secs((MAX_CACHED_THUMBS + 2 - i) as u64)).unwrap();
+        }
+        let old = File::create(dir.path().join("old.png")).unwrap();
+        old.set_modified(now - std::time::Duration::from_secs((MAX_THUMB_AGE_DAYS + 1) * 24 * 60 * 60)).unwrap();
+
+        prune_thumbs(dir.path());
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), MAX_CACHED_THUMBS);
+        assert!(!dir.path().join("old.png").exists());
+        assert!(!dir.path().join("0.png").exists());
+        assert!(!dir.path().join("1.png").exists());
+        assert!(dir.path().join("2.png").exists());
+    }
+}
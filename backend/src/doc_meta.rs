@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+use std::fs;
+use std::sync::{LazyLock, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::models::DocInfo;
+
+// --- Doc Metadata Extraction ---
+//
+// Parsed metadata is cached per path and reused while the file's mtime and
+// size are unchanged, so listing docs only costs a stat per doc.
+
+/// Files above this size are stat'ed but never read for front-matter or word counts.
+const MAX_TEXT_SCAN: u64 = 8 * 1024 * 1024;
+
+static CACHE: LazyLock<RwLock<HashMap<String, ParsedDoc>>> = LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// What `refresh` derives from a file's content, keyed by the stat it was read at.
+#[derive(Debug, Clone)]
+struct ParsedDoc {
+    modified: Option<SystemTime>,
+    size: u64,
+    word_count: usize,
+    title: Option<String>,
+    description: Option<String>,
+    owner: Option<String>,
+    category: Option<String>,
+    tags: Vec<String>,
+    projects: Vec<String>,
+}
+
+/// Metadata declared in a Markdown front-matter block (`---` delimited, `key: value` lines).
+#[derive(Debug, Default)]
+pub struct FrontMatter {
+    pub fields: HashMap<String, Vec<String>>,
+}
+
+impl FrontMatter {
+    fn scalar(&self, key: &str) -> Option<String> {
+        self.fields.get(key).and_then(|v| v.first().cloned())
+    }
+
+    fn list(&self, key: &str) -> Vec<String> {
+        self.fields.get(key).cloned().unwrap_or_default()
+    }
+}
+
+fn unquote(value: &str) -> String {
+    value.trim().trim_matches(|c| c == '"' || c == '\'').to_string()
+}
+
+/// Splits a document into its front-matter and body. Documents without a
+/// leading `---` block return an empty `FrontMatter` and the full text.
+pub fn parse_front_matter(text: &str) -> (FrontMatter, &str) {
+    let mut fm = FrontMatter::default();
+
+    let Some(rest) = text.strip_prefix("---\n").or_else(|| text.strip_prefix("---\r\n")) else {
+        return (fm, text);
+    };
+    // The block ends at the first line consisting of `---` alone
+    let mut offset = 0;
+    let mut closing = None;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end_matches(['\r', '\n']) == "---" {
+            closing = Some((offset, offset + line.len()));
+            break;
+        }
+        offset += line.len();
+    }
+    let Some((end, body_start)) = closing else {
+        return (fm, text);
+    };
+
+    let block = &rest[..end];
+    let body = &rest[body_start..];
+
+    let mut current_key: Option<String> = None;
+    for line in block.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        // Continuation of a YAML block list (`- item`)
+        if let Some(item) = trimmed.strip_prefix("- ") {
+            if let Some(key) = &current_key {
+                fm.fields.entry(key.clone()).or_default().push(unquote(item));
+            }
+            continue;
+        }
+
+        let Some((key, value)) = trimmed.split_once(':') else { continue };
+        let key = key.trim().to_lowercase();
+        let value = value.trim();
+
+        let values = if let Some(inner) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            inner.split(',').map(unquote).filter(|v| !v.is_empty()).collect()
+        } else if value.is_empty() {
+            vec![]
+        } else if matches!(key.as_str(), "tags" | "projects") {
+            value.split(',').map(unquote).filter(|v| !v.is_empty()).collect()
+        } else {
+            vec![unquote(value)]
+        };
+
+        fm.fields.insert(key.clone(), values);
+        current_key = Some(key);
+    }
+
+    (fm, body)
+}
+
+/// Returns the text of the first Markdown heading, if any.
+pub fn first_heading(body: &str) -> Option<String> {
+    body.lines()
+        .map(str::trim)
+        .find(|l| l.starts_with('#'))
+        .map(|l| l.trim_start_matches('#').trim().to_string())
+        .filter(|t| !t.is_empty())
+}
+
+fn parse(path: &str, modified: Option<SystemTime>, size: u64) -> Option<ParsedDoc> {
+    let text = fs::read_to_string(path).ok()?;
+    let (fm, body) = parse_front_matter(&text);
+    Some(ParsedDoc {
+        modified,
+        size,
+        word_count: body.split_whitespace().count(),
+        title: fm.scalar("title").or_else(|| first_heading(body)),
+        description: fm.scalar("description"),
+        owner: fm.scalar("owner").or_else(|| fm.scalar("author")),
+        category: fm.scalar("category"),
+        tags: fm.list("tags"),
+        projects: fm.list("projects"),
+    })
+}
+
+/// Refreshes the derived fields of `doc` (size, mtime, word count) and fills
+/// any metadata not set explicitly from the file's front-matter or first heading.
+/// The file is only re-read when its mtime or size changed since the last call.
+pub fn refresh(doc: &mut DocInfo) {
+    let Ok(metadata) = fs::metadata(&doc.path) else {
+        return;
+    };
+
+    let modified = metadata.modified().ok();
+    doc.size = Some(metadata.len());
+    doc.mtime = modified
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs());
+
+    if !metadata.is_file() || metadata.len() > MAX_TEXT_SCAN {
+        return;
+    }
+
+    let cached = CACHE.read().unwrap_or_else(|e| e.into_inner()).get(&doc.path)
+        .filter(|p| p.modified == modified && p.size == metadata.len())
+        .cloned();
+    let parsed = match cached {
+        Some(parsed) => parsed,
+        None => {
+            let Some(parsed) = parse(&doc.path, modified, metadata.len()) else {
+                return;
+            };
+            CACHE.write().unwrap_or_else(|e| e.into_inner()).insert(doc.path.clone(), parsed.clone());
+            parsed
+        }
+    };
+
+    doc.word_count = Some(parsed.word_count);
+    if doc.title.is_none() {
+        doc.title = parsed.title;
+    }
+    if doc.description.is_none() {
+        doc.description = parsed.description;
+    }
+    if doc.owner.is_none() {
+        doc.owner = parsed.owner;
+    }
+    if doc.category.is_none() {
+        doc.category = parsed.category;
+    }
+    if doc.tags.is_empty() {
+        doc.tags = parsed.tags;
+    }
+    if doc.projects.is_empty() {
+        doc.projects = parsed.projects;
+    }
+}
+
+/// `refresh` for a whole list, off the async runtime since cache misses read files.
+pub async fn refresh_all(docs: Vec<DocInfo>) -> Vec<DocInfo> {
+    tokio::task::spawn_blocking(move || {
+        let mut docs = docs;
+        docs.iter_mut().for_each(refresh);
+        docs
+    }).await.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_without_front_matter_is_all_body() {
+        let text = "# Title\nbody\n";
+        let (fm, body) = parse_front_matter(text);
+        assert!(fm.fields.is_empty());
+        assert_eq!(body, text);
+    }
+
+    #[test]
+    fn unterminated_block_is_treated_as_body() {
+        let text = "---\ntitle: Draft\nno closing line\n";
+        let (fm, body) = parse_front_matter(text);
+        assert!(fm.fields.is_empty());
+        assert_eq!(body, text);
+    }
+
+    #[test]
+    fn scalars_lists_and_quotes_are_parsed() {
+        let text = "---\r\nTitle: \"Launch Plan\"\r\n# comment\r\ntags: [ops, 'infra']\r\nprojects: alpha, beta\r\nauthor: Ana\r\n---\r\n# Heading\r\nBody text\r\n";
+        let (fm, body) = parse_front_matter(text);
+        assert_eq!(fm.scalar("title").as_deref(), Some("Launch Plan"));
+        assert_eq!(fm.list("tags"), ["ops", "infra"]);
+        assert_eq!(fm.list("projects"), ["alpha", "beta"]);
+        assert_eq!(fm.scalar("author").as_deref(), Some("Ana"));
+        assert_eq!(body, "# Heading\r\nBody text\r\n");
+    }
+
+    #[test]
+    fn block_lists_collect_under_the_previous_key() {
+        let text = "---\ntags:\n  - one\n  - \"two\"\ncategory: notes\n---\n";
+        let (fm, body) = parse_front_matter(text);
+        assert_eq!(fm.list("tags"), ["one", "two"]);
+        assert_eq!(fm.scalar("category").as_deref(), Some("notes"));
+        assert_eq!(body, "");
+    }
+
+    #[test]
+    fn first_heading_strips_the_marker() {
+        assert_eq!(first_heading("intro\n  ## Real Title \n# Later\n").as_deref(), Some("Real Title"));
+        assert_eq!(first_heading("no headings"), None);
+    }
+}
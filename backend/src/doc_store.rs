@@ -0,0 +1,243 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{Error, ErrorKind, Result};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Local};
+use similar::TextDiff;
+
+use crate::models::{DocInfo, DocVersion};
+use crate::storage::{read_json, atomic_write_json, FileLock};
+
+// --- Content-Addressed Doc History ---
+//
+// Every distinct revision of a registered doc is stored once under
+// `doc_store/objects/<hash>` (BLAKE3 of the raw bytes). `versions.json`
+// maps each doc path to its ordered list of revisions, oldest first.
+// Histories are pruned by count and age (the latest revision is always
+// kept), and objects no longer referenced by any history are deleted.
+
+const STORE_DIR: &str = "doc_store";
+const OBJECTS_DIR: &str = "doc_store/objects";
+const INDEX_FILE: &str = "doc_store/versions.json";
+const MAX_SNAPSHOT_BYTES: u64 = 8 * 1024 * 1024; // Larger files are not versioned
+const MAX_VERSIONS_PER_DOC: usize = 100;
+const MAX_VERSION_AGE_DAYS: i64 = 90;
+
+type VersionIndex = HashMap<String, Vec<DocVersion>>;
+
+fn object_path(hash: &str) -> PathBuf {
+    PathBuf::from(OBJECTS_DIR).join(hash)
+}
+
+fn store_object(bytes: &[u8]) -> Result<String> {
+    let hash = blake3::hash(bytes).to_hex().to_string();
+    let path = object_path(&hash);
+    if !path.exists() {
+        fs::create_dir_all(OBJECTS_DIR)?;
+        let tmp = tempfile::NamedTempFile::new_in(OBJECTS_DIR)?;
+        fs::write(tmp.path(), bytes)?;
+        tmp.persist(&path).map_err(Error::other)?;
+    }
+    Ok(hash)
+}
+
+/// Drops revisions beyond the per-doc count and age limits, keeping the latest.
+/// Returns true when anything was removed.
+fn prune(history: &mut Vec<DocVersion>) -> bool {
+    let before = history.len();
+    let cutoff = Local::now() - Duration::days(MAX_VERSION_AGE_DAYS);
+    let keep_from = history.len().saturating_sub(MAX_VERSIONS_PER_DOC);
+    let last = history.len().saturating_sub(1);
+    let mut i = 0;
+    history.retain(|v| {
+        let recent = DateTime::parse_from_rfc3339(&v.captured).map(|t| t >= cutoff).unwrap_or(true);
+        let keep = i == last || (i >= keep_from && recent);
+        i += 1;
+        keep
+    });
+    history.len() != before
+}
+
+/// Deletes stored objects that no revision in `index` refers to.
+fn collect_garbage(index: &VersionIndex) {
+    let referenced: HashSet<&str> = index.values().flatten().map(|v| v.hash.as_str()).collect();
+    let Ok(entries) = fs::read_dir(OBJECTS_DIR) else { return };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        if !referenced.contains(name.to_string_lossy().as_ref()) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+fn lock_index() -> Result<FileLock> {
+    fs::create_dir_all(STORE_DIR)?;
+    FileLock::new(INDEX_FILE)
+}
+
+/// Records the current content of `path` if it differs from the latest stored revision.
+/// Returns the new revision, or `None` when the content is unchanged.
+pub fn snapshot(path: &str) -> Result<Option<DocVersion>> {
+    let size = fs::metadata(path)?.len();
+    if size > MAX_SNAPSHOT_BYTES {
+        return Err(Error::new(ErrorKind::InvalidData, format!("File too large to version ({} bytes, limit {})", size, MAX_SNAPSHOT_BYTES)));
+    }
+    let bytes = fs::read(path)?;
+
+    let _lock = lock_index()?;
+    let mut index: VersionIndex = read_json(INDEX_FILE).unwrap_or_default();
+    let history = index.entry(path.to_string()).or_default();
+
+    let hash = blake3::hash(&bytes).to_hex().to_string();
+    if history.last().is_some_and(|v| v.hash == hash) {
+        return Ok(None);
+    }
+
+    store_object(&bytes)?;
+    let version = DocVersion {
+        hash,
+        size: bytes.len() as u64,
+        captured: Local::now().to_rfc3339(),
+    };
+    history.push(version.clone());
+    let pruned = prune(history);
+    atomic_write_json(INDEX_FILE, &index)?;
+    if pruned {
+        collect_garbage(&index);
+    }
+
+    Ok(Some(version))
+}
+
+/// Snapshots every registered doc and returns the ones whose content changed.
+/// Missing and oversized files are skipped silently.
+pub fn snapshot_registered(docs: &[DocInfo]) -> Vec<&DocInfo> {
+    docs.iter()
+        .filter(|d| matches!(snapshot(&d.path), Ok(Some(_))))
+        .collect()
+}
+
+pub fn list_versions(path: &str) -> Vec<DocVersion> {
+    let mut index: VersionIndex = read_json(INDEX_FILE).unwrap_or_default();
+    index.remove(path).unwrap_or_default()
+}
+
+/// Resolves a revision reference (full hash or unique prefix) within a doc's history.
+pub fn resolve(path: &str, rev: &str) -> Result<DocVersion> {
+    let matches: Vec<DocVersion> = list_versions(path)
+        .into_iter()
+        .filter(|v| v.hash.starts_with(rev))
+        .collect();
+
+    match matches.first() {
+        None => Err(Error::new(ErrorKind::NotFound, format!("Unknown version: {}", rev))),
+        Some(v) if matches.iter().all(|m| m.hash == v.hash) => Ok(v.clone()),
+        Some(_) => Err(Error::new(ErrorKind::InvalidInput, format!("Ambiguous version: {}", rev))),
+    }
+}
+
+pub fn load_object(hash: &str) -> Result<Vec<u8>> {
+    fs::read(object_path(hash))
+}
+
+/// Reads either a stored revision or, for `"current"`, the file on disk.
+fn load_revision(path: &str, rev: &str) -> Result<(String, Vec<u8>)> {
+    if rev == "current" {
+        return Ok(("current".to_string(), fs::read(path)?));
+    }
+    let version = resolve(path, rev)?;
+    let bytes = load_object(&version.hash)?;
+    Ok((version.hash, bytes))
+}
+
+/// Produces a unified diff between two revisions of `path`.
+pub fn diff(path: &str, from: &str, to: &str) -> Result<String> {
+    let (from_label, from_bytes) = load_revision(path, from)?;
+    let (to_label, to_bytes) = load_revision(path, to)?;
+
+    let (Ok(old), Ok(new)) = (String::from_utf8(from_bytes), String::from_utf8(to_bytes)) else {
+        return Err(Error::new(ErrorKind::InvalidData, "Binary content cannot be diffed"));
+    };
+
+    let short = |label: &str| label.chars().take(12).collect::<String>();
+    Ok(TextDiff::from_lines(&old, &new)
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("{}@{}", path, short(&from_label)), &format!("{}@{}", path, short(&to_label)))
+        .to_string())
+}
+
+/// Writes a stored revision back to `path`. The current content is
+/// snapshotted first so the restore itself can be undone.
+pub fn restore(path: &str, rev: &str) -> Result<DocVersion> {
+    let version = resolve(path, rev)?;
+    let bytes = load_object(&version.hash)?;
+
+    let existing = fs::metadata(path).ok();
+    if existing.is_some() {
+        snapshot(path)?;
+    }
+
+    // Write beside the target and rename, so a crash never leaves a truncated doc
+    let dir = Path::new(path).parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let tmp = tempfile::NamedTempFile::new_in(dir)?;
+    fs::write(tmp.path(), &bytes)?;
+    if let Some(meta) = existing {
+        fs::set_permissions(tmp.path(), meta.permissions())?;
+    }
+    tmp.persist(path).map_err(Error::other)?;
+    snapshot(path)?;
+
+    Ok(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(n: usize, age_days: i64) -> DocVersion {
+        DocVersion {
+            hash: format!("hash{}", n),
+            size: n as u64,
+            captured: (Local::now() - Duration::days(age_days)).to_rfc3339(),
+        }
+    }
+
+    fn hashes(history: &[DocVersion]) -> Vec<&str> {
+        history.iter().map(|v| v.hash.as_str()).collect()
+    }
+
+    #[test]
+    fn prune_keeps_recent_histories_within_the_limit() {
+        let mut history: Vec<DocVersion> = (0..3).map(|n| version(n, 1)).collect();
+        assert!(!prune(&mut history));
+        assert_eq!(history.len(), 3);
+    }
+
+    #[test]
+    fn prune_drops_the_oldest_beyond_the_count_limit() {
+        let mut history: Vec<DocVersion> = (0..MAX_VERSIONS_PER_DOC + 2).map(|n| version(n, 0)).collect();
+        assert!(prune(&mut history));
+        assert_eq!(history.len(), MAX_VERSIONS_PER_DOC);
+        assert_eq!(history[0].hash, "hash2");
+    }
+
+    #[test]
+    fn prune_drops_expired_revisions_but_always_keeps_the_latest() {
+        let mut history = vec![version(0, MAX_VERSION_AGE_DAYS + 10), version(1, 1), version(2, MAX_VERSION_AGE_DAYS + 1)];
+        assert!(prune(&mut history));
+        assert_eq!(hashes(&history), ["hash1", "hash2"]);
+
+        let mut only = vec![version(0, MAX_VERSION_AGE_DAYS + 10)];
+        assert!(!prune(&mut only));
+        assert_eq!(only.len(), 1);
+    }
+
+    #[test]
+    fn prune_keeps_revisions_with_unparseable_timestamps() {
+        let mut history = vec![DocVersion { captured: "garbage".into(), ..version(0, 0) }, version(1, 0)];
+        assert!(!prune(&mut history));
+        assert_eq!(hashes(&history), ["hash0", "hash1"]);
+    }
+}
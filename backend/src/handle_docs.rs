@@ -1,23 +1,122 @@
 use axum::{
+    body::Body,
     extract::{Json, Query},
     response::IntoResponse,
-    http::StatusCode,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
 };
+use tokio_util::io::ReaderStream;
 use crate::models::DocInfo;
 use crate::storage::{read_json, atomic_write_json, FileLock};
+use crate::doc_content::{self, ByteRange};
+use crate::{doc_meta, doc_store, search};
 
 const DOCS_FILE: &str = "docs.json";
+const PROJECTS_FILE: &str = "projects.json";
+const DOC_SNAPSHOT_INTERVAL: u64 = 30;
+
+#[derive(serde::Deserialize)]
+pub struct DocsQuery {
+    tag: Option<String>,
+    category: Option<String>,
+    project: Option<String>,
+    sort: Option<String>,  // "added" | "title" | "mtime" | "size" | "words"
+    order: Option<String>, // "asc" | "desc"
+}
+
+pub async fn get_docs(Query(query): Query<DocsQuery>) -> impl IntoResponse {
+    let mut docs = doc_meta::refresh_all(read_json::<Vec<DocInfo>>(DOCS_FILE).unwrap_or_default()).await;
+
+    if let Some(tag) = &query.tag {
+        docs.retain(|d| d.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)));
+    }
+    if let Some(category) = &query.category {
+        docs.retain(|d| d.category.as_deref().is_some_and(|c| c.eq_ignore_ascii_case(category)));
+    }
+    if let Some(project) = &query.project {
+        docs.retain(|d| d.projects.iter().any(|p| p == project));
+    }
+
+    match query.sort.as_deref() {
+        Some("title") => docs.sort_by_key(|d| d.title.clone().unwrap_or_default().to_lowercase()),
+        Some("mtime") => docs.sort_by_key(|d| d.mtime.unwrap_or(0)),
+        Some("size") => docs.sort_by_key(|d| d.size.unwrap_or(0)),
+        Some("words") => docs.sort_by_key(|d| d.word_count.unwrap_or(0)),
+        Some("added") => docs.sort_by(|a, b| a.added.cmp(&b.added)),
+        _ => {}
+    }
+    if query.order.as_deref() == Some("desc") {
+        docs.reverse();
+    }
 
-pub async fn get_docs() -> impl IntoResponse {
-    let docs = read_json::<Vec<DocInfo>>(DOCS_FILE).unwrap_or_default();
     (StatusCode::OK, Json(serde_json::json!({ "docs": docs }))).into_response()
 }
 
+/// Fields left out are kept; fields sent as `null` are cleared.
+#[derive(serde::Deserialize)]
+pub struct DocPatch {
+    path: String,
+    #[serde(default, deserialize_with = "nullable")]
+    title: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    tags: Option<Option<Vec<String>>>,
+    #[serde(default, deserialize_with = "nullable")]
+    description: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    owner: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    category: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    projects: Option<Option<Vec<String>>>,
+}
+
+/// Tells an explicit `null` (`Some(None)`) apart from a missing field (`None`).
+fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: serde::Deserialize<'de>,
+{
+    serde::Deserialize::deserialize(deserializer).map(Some)
+}
+
+pub async fn patch_docs(Json(patch): Json<DocPatch>) -> impl IntoResponse {
+    if let Some(Some(projects)) = &patch.projects {
+        let known = read_json::<Vec<crate::models::Project>>(PROJECTS_FILE).unwrap_or_default();
+        if let Some(unknown) = projects.iter().find(|p| !known.iter().any(|k| &k.name == *p)) {
+            return (StatusCode::UNPROCESSABLE_ENTITY, format!("Unknown project: {}", unknown)).into_response();
+        }
+    }
+
+    let _lock = match FileLock::new(DOCS_FILE) {
+        Ok(l) => l,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Docs lock failed").into_response(),
+    };
+
+    let mut docs: Vec<DocInfo> = read_json(DOCS_FILE).unwrap_or_default();
+    let Some(doc) = docs.iter_mut().find(|d| d.path == patch.path) else {
+        return (StatusCode::NOT_FOUND, "Doc not registered").into_response();
+    };
+
+    if let Some(title) = patch.title { doc.title = title; }
+    if let Some(tags) = patch.tags { doc.tags = tags.unwrap_or_default(); }
+    if let Some(description) = patch.description { doc.description = description; }
+    if let Some(owner) = patch.owner { doc.owner = owner; }
+    if let Some(category) = patch.category { doc.category = category; }
+    if let Some(projects) = patch.projects { doc.projects = projects.unwrap_or_default(); }
+
+    let mut updated = doc.clone();
+    if let Err(e) = atomic_write_json(DOCS_FILE, &docs) {
+        return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response();
+    }
+    search::index_doc(&updated);
+
+    doc_meta::refresh(&mut updated);
+    (StatusCode::OK, Json(updated)).into_response()
+}
+
 pub async fn fragment_logs() -> impl IntoResponse {
     use crate::models::Message;
     use chrono::Local;
     use std::fs;
-    use std::path::Path;
 
     let log_file = "conversation_log.json";
     let messages: Vec<Message> = read_json(log_file).unwrap_or_default();
@@ -52,12 +151,15 @@ pub async fn fragment_logs() -> impl IntoResponse {
         let filename = format!("Log_{}_{}.json", agent, date_str);
         let path = format!("{}/{}", fragments_dir, filename);
         
-        if let Ok(_) = atomic_write_json(&path, &logs) {
+        if atomic_write_json(&path, &logs).is_ok() {
+            let _ = doc_store::snapshot(&path);
             new_docs.push(DocInfo {
                 path: path.clone(),
                 added: Local::now().to_rfc3339(),
                 category: Some(format!("Log_{}", agent)),
                 is_fragment: Some(true),
+                owner: Some(agent.to_string()),
+                ..Default::default()
             });
         }
     };
@@ -68,6 +170,8 @@ pub async fn fragment_logs() -> impl IntoResponse {
 
     // Clear main log after fragmentation
     let _ = atomic_write_json(log_file, &Vec::<Message>::new());
+    search::reindex_log();
+    new_docs.iter().for_each(search::index_doc);
 
     // Register in docs.json
     let _lock = match FileLock::new(DOCS_FILE) {
@@ -91,6 +195,8 @@ pub async fn post_docs(Json(doc_info): Json<DocInfo>) -> impl IntoResponse {
 
     // Check duplicates
     if !docs.iter().any(|d| d.path == doc_info.path) {
+        let _ = doc_store::snapshot(&doc_info.path);
+        search::index_doc(&doc_info);
         docs.push(doc_info);
         if let Err(e) = atomic_write_json(DOCS_FILE, &docs) {
             return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response();
@@ -105,17 +211,105 @@ pub struct ContentParams {
     path: String,
 }
 
-pub async fn get_docs_content(Query(params): Query<ContentParams>) -> impl IntoResponse {
-    use std::fs;
-    
+#[derive(serde::Deserialize)]
+pub struct ContentQuery {
+    path: String,
+    head: Option<usize>,  // first N lines of a text file
+    tail: Option<usize>,  // last N lines of a text file
+    thumb: Option<u32>,   // PNG preview of an image, max edge in px
+}
+
+fn io_error_status(e: &std::io::Error) -> StatusCode {
+    match e.kind() {
+        std::io::ErrorKind::NotFound => StatusCode::NOT_FOUND,
+        std::io::ErrorKind::PermissionDenied => StatusCode::FORBIDDEN,
+        std::io::ErrorKind::InvalidData => StatusCode::UNPROCESSABLE_ENTITY,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub async fn get_docs_content(Query(params): Query<ContentQuery>, headers: HeaderMap) -> impl IntoResponse {
+    use std::path::PathBuf;
+    use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
     // SECURITY NOTE: This allows reading any file on the system.
     // In a prod environment, strictly limit paths. 
     // For this local tool, we assume user trusts themselves.
-    
-    match fs::read_to_string(&params.path) {
-        Ok(content) => (StatusCode::OK, content).into_response(),
-        Err(e) => (StatusCode::NOT_FOUND, format!("Error reading file: {}", e)).into_response(),
+
+    let path = PathBuf::from(&params.path);
+    let metadata = match tokio::fs::metadata(&path).await {
+        Ok(m) if m.is_file() => m,
+        Ok(_) => return (StatusCode::BAD_REQUEST, "Not a regular file").into_response(),
+        Err(e) => return (io_error_status(&e), format!("Error reading file: {}", e)).into_response(),
+    };
+    let size = metadata.len();
+
+    let sniff_path = path.clone();
+    let mime = match tokio::task::spawn_blocking(move || doc_content::sniff_mime(&sniff_path)).await {
+        Ok(Ok(mime)) => mime,
+        Ok(Err(e)) => return (io_error_status(&e), format!("Error reading file: {}", e)).into_response(),
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    };
+
+    // --- Image previews ---
+    if let Some(thumb) = params.thumb {
+        if !doc_content::is_thumbnailable(&mime) {
+            return (StatusCode::UNSUPPORTED_MEDIA_TYPE, format!("No preview available for {}", mime)).into_response();
+        }
+        return match tokio::task::spawn_blocking(move || doc_content::thumbnail(&path, thumb)).await {
+            Ok(Ok(png)) => ([(header::CONTENT_TYPE, "image/png")], png).into_response(),
+            Ok(Err(e)) => (io_error_status(&e), format!("Preview failed: {}", e)).into_response(),
+            Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        };
+    }
+
+    // --- Line windows for large text logs ---
+    if params.head.is_some() || params.tail.is_some() {
+        if !doc_content::is_text(&mime) {
+            return (StatusCode::UNSUPPORTED_MEDIA_TYPE, format!("Line windows require text content, got {}", mime)).into_response();
+        }
+        let (head, tail) = (params.head, params.tail);
+        let window = tokio::task::spawn_blocking(move || match head {
+            Some(n) => doc_content::read_head(&path, n),
+            None => doc_content::read_tail(&path, tail.unwrap_or_default()),
+        }).await;
+        return match window {
+            Ok(Ok(text)) => ([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], text).into_response(),
+            Ok(Err(e)) => (io_error_status(&e), format!("Error reading file: {}", e)).into_response(),
+            Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        };
+    }
+
+    // --- Streaming with Range support ---
+    let range = headers.get(header::RANGE).and_then(|v| v.to_str().ok());
+    let (status, start, len) = match doc_content::parse_range(range, size) {
+        ByteRange::Full => (StatusCode::OK, 0, size),
+        ByteRange::Partial(start, end) => (StatusCode::PARTIAL_CONTENT, start, end - start + 1),
+        ByteRange::Unsatisfiable => {
+            return (StatusCode::RANGE_NOT_SATISFIABLE, [(header::CONTENT_RANGE, format!("bytes */{}", size))]).into_response();
+        }
+    };
+
+    let mut file = match tokio::fs::File::open(&path).await {
+        Ok(f) => f,
+        Err(e) => return (io_error_status(&e), format!("Error reading file: {}", e)).into_response(),
+    };
+    if start > 0 && let Err(e) = file.seek(std::io::SeekFrom::Start(start)).await {
+        return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response();
+    }
+
+    let body = Body::from_stream(ReaderStream::new(file.take(len)));
+    let mut response = (status, body).into_response();
+    let h = response.headers_mut();
+    h.insert(header::CONTENT_TYPE, HeaderValue::from_str(&mime).unwrap_or(HeaderValue::from_static("application/octet-stream")));
+    h.insert(header::CONTENT_LENGTH, HeaderValue::from(len));
+    h.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    if status == StatusCode::PARTIAL_CONTENT
+        && let Ok(v) = HeaderValue::from_str(&format!("bytes {}-{}/{}", start, start + len - 1, size))
+    {
+        h.insert(header::CONTENT_RANGE, v);
     }
+    response
 }
 
 pub async fn delete_doc(Query(params): Query<ContentParams>) -> impl IntoResponse {
@@ -127,20 +321,102 @@ pub async fn delete_doc(Query(params): Query<ContentParams>) -> impl IntoRespons
     let mut docs: Vec<DocInfo> = read_json(DOCS_FILE).unwrap_or_default();
     
     // Check if it's a fragment and delete physical file
-    if let Some(doc) = docs.iter().find(|d| d.path == params.path) {
-        if doc.is_fragment.unwrap_or(false) {
-            let _ = std::fs::remove_file(&params.path);
-        }
+    if let Some(doc) = docs.iter().find(|d| d.path == params.path)
+        && doc.is_fragment.unwrap_or(false)
+    {
+        let _ = std::fs::remove_file(&params.path);
     }
 
     let original_len = docs.len();
     docs.retain(|d| d.path != params.path);
+    search::remove_doc(&params.path);
 
-    if docs.len() < original_len {
-        if let Err(e) = atomic_write_json(DOCS_FILE, &docs) {
-            return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response();
-        }
+    if docs.len() < original_len
+        && let Err(e) = atomic_write_json(DOCS_FILE, &docs)
+    {
+        return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response();
     }
 
     (StatusCode::OK, Json(serde_json::json!({"status": "ok"}))).into_response()
 }
+
+// --- Version History ---
+
+pub async fn get_doc_versions(Query(params): Query<ContentParams>) -> impl IntoResponse {
+    let versions = doc_store::list_versions(&params.path);
+    (StatusCode::OK, Json(serde_json::json!({ "path": params.path, "versions": versions }))).into_response()
+}
+
+#[derive(serde::Deserialize)]
+pub struct DiffParams {
+    path: String,
+    from: String,
+    #[serde(default = "current_rev")]
+    to: String,
+}
+
+fn current_rev() -> String {
+    "current".into()
+}
+
+fn store_error_status(e: &std::io::Error) -> StatusCode {
+    match e.kind() {
+        std::io::ErrorKind::NotFound => StatusCode::NOT_FOUND,
+        std::io::ErrorKind::InvalidInput | std::io::ErrorKind::InvalidData => StatusCode::UNPROCESSABLE_ENTITY,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub async fn get_doc_diff(Query(params): Query<DiffParams>) -> impl IntoResponse {
+    match doc_store::diff(&params.path, &params.from, &params.to) {
+        Ok(diff) => (StatusCode::OK, diff).into_response(),
+        Err(e) => (store_error_status(&e), e.to_string()).into_response(),
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct RestoreParams {
+    path: String,
+    version: String,
+}
+
+pub async fn post_doc_restore(Json(params): Json<RestoreParams>) -> impl IntoResponse {
+    println!("⏪ Restoring {} to version {}", params.path, params.version);
+
+    match doc_store::restore(&params.path, &params.version) {
+        Ok(version) => {
+            if let Some(doc) = read_json::<Vec<DocInfo>>(DOCS_FILE).unwrap_or_default().iter().find(|d| d.path == params.path) {
+                search::index_doc(doc);
+            }
+            (StatusCode::OK, Json(serde_json::json!({"status": "ok", "restored": version}))).into_response()
+        }
+        Err(e) => (store_error_status(&e), e.to_string()).into_response(),
+    }
+}
+
+/// Periodically snapshots registered docs so edits made outside the API are captured.
+pub async fn run_version_watcher() {
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(DOC_SNAPSHOT_INTERVAL));
+    loop {
+        interval.tick().await;
+        let _ = tokio::task::spawn_blocking(|| {
+            let docs = read_json::<Vec<DocInfo>>(DOCS_FILE).unwrap_or_default();
+            doc_store::snapshot_registered(&docs).into_iter().for_each(search::index_doc);
+        }).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn doc_patch_tells_null_from_missing() {
+        let patch: DocPatch = serde_json::from_str(r#"{"path": "a.md", "owner": null, "tags": null, "category": "ops"}"#).unwrap();
+        assert_eq!(patch.owner, Some(None));
+        assert_eq!(patch.tags, Some(None));
+        assert_eq!(patch.category, Some(Some("ops".to_string())));
+        assert_eq!(patch.title, None);
+        assert_eq!(patch.projects, None);
+    }
+}
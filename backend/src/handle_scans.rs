@@ -0,0 +1,34 @@
+use axum::{
+    extract::{Json, Path},
+    response::IntoResponse,
+    http::StatusCode,
+};
+use crate::scan_jobs::{self, ScanKind};
+
+pub async fn get_scans() -> impl IntoResponse {
+    (StatusCode::OK, Json(scan_jobs::list())).into_response()
+}
+
+pub async fn get_scan(Path(id): Path<String>) -> impl IntoResponse {
+    match scan_jobs::get(&id) {
+        Some(job) => (StatusCode::OK, Json(job)).into_response(),
+        None => (StatusCode::NOT_FOUND, "No scan job with that id").into_response(),
+    }
+}
+
+/// Starts (or joins) a scan, e.g. `{"kind": "dir_size", "path": "/home/a2/.cache"}`.
+pub async fn post_scan(Json(kind): Json<ScanKind>) -> impl IntoResponse {
+    if let ScanKind::DirSize { path } | ScanKind::DiskTree { path } = &kind
+        && !std::path::Path::new(path).is_dir()
+    {
+        return (StatusCode::BAD_REQUEST, "Not a directory").into_response();
+    }
+    (StatusCode::ACCEPTED, Json(scan_jobs::start(kind))).into_response()
+}
+
+pub async fn post_cancel_scan(Path(id): Path<String>) -> impl IntoResponse {
+    match scan_jobs::cancel(&id) {
+        Some(job) => (StatusCode::OK, Json(job)).into_response(),
+        None => (StatusCode::NOT_FOUND, "No scan job with that id").into_response(),
+    }
+}
@@ -0,0 +1,38 @@
+use axum::{
+    extract::{Json, Query},
+    response::IntoResponse,
+    http::StatusCode,
+};
+use crate::search::{self, SearchQuery};
+
+const DEFAULT_LIMIT: usize = 20;
+
+#[derive(serde::Deserialize)]
+pub struct SearchParams {
+    q: String,
+    sender: Option<String>,
+    category: Option<String>,
+    kind: Option<String>, // "message" | "fragment" | "doc"
+    from: Option<String>,
+    to: Option<String>,
+    limit: Option<usize>,
+}
+
+pub async fn get_search(Query(params): Query<SearchParams>) -> impl IntoResponse {
+    let query = SearchQuery {
+        text: params.q.clone(),
+        sender: params.sender,
+        category: params.category,
+        kind: params.kind,
+        from: params.from,
+        to: params.to,
+        limit: params.limit.unwrap_or(DEFAULT_LIMIT),
+    };
+
+    let (total, results) = search::search(&query);
+    (StatusCode::OK, Json(serde_json::json!({
+        "query": params.q,
+        "total": total,
+        "results": results,
+    }))).into_response()
+}
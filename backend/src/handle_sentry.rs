@@ -0,0 +1,77 @@
+use axum::{
+    extract::{Json, Query},
+    response::IntoResponse,
+    http::StatusCode,
+};
+use chrono::Local;
+use std::sync::{LazyLock, RwLock};
+use crate::models::{SentryReport, Severity};
+use crate::storage::{read_json, atomic_write_json, FileLock};
+
+pub const SENTRY_REPORTS_FILE: &str = "sentry_reports.json";
+const MAX_REPORTS: usize = 500; // Oldest reports are dropped beyond this
+const DEFAULT_LIMIT: usize = 20;
+
+// The dashboard polls the latest report, so it is kept in memory instead of
+// re-parsing the whole history each time. Loaded from disk once at first use.
+static LATEST: LazyLock<RwLock<Option<SentryReport>>> = LazyLock::new(|| {
+    RwLock::new(read_json::<Vec<SentryReport>>(SENTRY_REPORTS_FILE).ok().and_then(|mut r| r.pop()))
+});
+
+#[derive(serde::Deserialize)]
+pub struct ReportsQuery {
+    limit: Option<usize>,
+    severity: Option<Severity>, // Only reports with at least one finding at this level or above
+}
+
+/// The most recent report the Sentry daemon posted, if any.
+pub fn latest_report() -> Option<SentryReport> {
+    LATEST.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+pub async fn get_sentry_reports(Query(params): Query<ReportsQuery>) -> impl IntoResponse {
+    let reports: Vec<SentryReport> = read_json(SENTRY_REPORTS_FILE).unwrap_or_default();
+    let total = reports.len();
+
+    let results: Vec<SentryReport> = reports.into_iter()
+        .rev()
+        .filter(|r| params.severity.is_none_or(|min| r.findings.iter().any(|f| f.severity >= min)))
+        .take(params.limit.unwrap_or(DEFAULT_LIMIT))
+        .collect();
+
+    (StatusCode::OK, Json(serde_json::json!({
+        "total": total,
+        "reports": results,
+    }))).into_response()
+}
+
+pub async fn get_latest_sentry_report() -> impl IntoResponse {
+    match latest_report() {
+        Some(report) => (StatusCode::OK, Json(report)).into_response(),
+        None => (StatusCode::NOT_FOUND, Json(serde_json::json!({"error": "No Sentry reports yet"}))).into_response(),
+    }
+}
+
+pub async fn post_sentry_report(Json(mut report): Json<SentryReport>) -> impl IntoResponse {
+    let _lock = match FileLock::new(SENTRY_REPORTS_FILE) {
+        Ok(l) => l,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Lock failed").into_response(),
+    };
+
+    let mut reports: Vec<SentryReport> = read_json(SENTRY_REPORTS_FILE).unwrap_or_default();
+    report.id = Some(reports.last().and_then(|r| r.id).map_or(0, |id| id + 1));
+    if report.timestamp.is_empty() {
+        report.timestamp = Local::now().to_rfc3339();
+    }
+    reports.push(report.clone());
+    if reports.len() > MAX_REPORTS {
+        reports.drain(..reports.len() - MAX_REPORTS);
+    }
+
+    if let Err(e) = atomic_write_json(SENTRY_REPORTS_FILE, &reports) {
+        return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response();
+    }
+    *LATEST.write().unwrap_or_else(|e| e.into_inner()) = Some(report.clone());
+
+    (StatusCode::CREATED, Json(report)).into_response()
+}
@@ -5,6 +5,18 @@ pub mod sentry;
 pub mod automation;
 pub mod system;
 pub mod handle_docs;
+pub mod doc_store;
+pub mod doc_meta;
+pub mod doc_content;
+pub mod search;
+pub mod handle_search;
+pub mod handle_sentry;
+pub mod artifacts;
+pub mod cleanup;
+pub mod disk_tree;
+pub mod mounts;
+pub mod scan_jobs;
+pub mod handle_scans;
 
 
 use axum::{
@@ -15,15 +27,37 @@ use std::net::SocketAddr;
 use tower_http::cors::CorsLayer;
 
 pub async fn run_server() {
+    tokio::task::spawn_blocking(search::rebuild);
+    tokio::spawn(handle_docs::run_version_watcher());
+    tokio::spawn(cleanup::trash::run_purge_scheduler());
+    tokio::spawn(cleanup::policy::run_policy_scheduler());
+
     let app = Router::new()
         .route("/api/messages", get(api::get_messages).post(api::post_message))
         .route("/api/state", get(api::get_state).post(api::post_state))
         .route("/api/dashboard", get(api::get_dashboard).post(api::post_dashboard))
-        .route("/api/docs", get(handle_docs::get_docs).post(handle_docs::post_docs).delete(handle_docs::delete_doc))
+        .route("/api/docs", get(handle_docs::get_docs).post(handle_docs::post_docs).patch(handle_docs::patch_docs).delete(handle_docs::delete_doc))
         .route("/api/docs/content", get(handle_docs::get_docs_content))
+        .route("/api/docs/versions", get(handle_docs::get_doc_versions))
+        .route("/api/docs/diff", get(handle_docs::get_doc_diff))
+        .route("/api/docs/restore", post(handle_docs::post_doc_restore))
         .route("/api/disk/cleanup", get(api::get_cleanup_candidates).post(api::post_cleanup))
+        .route("/api/disk/artifacts", get(api::get_artifacts))
+        .route("/api/disk/tree", get(api::get_disk_tree))
+        .route("/api/disk/providers", get(api::get_cleanup_providers))
+        .route("/api/disk/trash", get(api::get_trash))
+        .route("/api/disk/restore", post(api::post_disk_restore))
+        .route("/api/disk/policies", get(api::get_cleanup_policies))
+        .route("/api/disk/policies/runs", get(api::get_cleanup_policy_runs))
+        .route("/api/disk/policies/run", post(api::post_cleanup_policy_run))
+        .route("/api/scans", get(handle_scans::get_scans).post(handle_scans::post_scan))
+        .route("/api/scans/{id}", get(handle_scans::get_scan))
+        .route("/api/scans/{id}/cancel", post(handle_scans::post_cancel_scan))
         .route("/api/shutdown", post(api::post_shutdown))
         .route("/api/logs/fragment", post(handle_docs::fragment_logs))
+        .route("/api/search", get(handle_search::get_search))
+        .route("/api/sentry/reports", get(handle_sentry::get_sentry_reports).post(handle_sentry::post_sentry_report))
+        .route("/api/sentry/reports/latest", get(handle_sentry::get_latest_sentry_report))
         .route("/api/podman", post(api::post_podman))
         .layer(CorsLayer::permissive());
 
@@ -49,21 +49,72 @@ pub struct Project {
     pub tags: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct DocInfo {
     pub path: String,
     pub added: String,
     pub category: Option<String>,
     pub is_fragment: Option<bool>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub owner: Option<String>, // Owning agent or person
+    #[serde(default)]
+    pub projects: Vec<String>, // Linked `Project` names
+    // Derived from the file on disk; refreshed on every listing.
+    #[serde(default)]
+    pub size: Option<u64>,
+    #[serde(default)]
+    pub mtime: Option<u64>,
+    #[serde(default)]
+    pub word_count: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DocVersion {
+    pub hash: String,
+    pub size: u64,
+    pub captured: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DiskStats {
+    // Root filesystem, kept for existing consumers; `mounts` covers every watched filesystem
     pub total: u64,
     pub used: u64,
     pub free: u64,
     pub usage_pct: f64,
     pub workspace_size: u64,
+    #[serde(default)]
+    pub mounts: Vec<MountStats>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct MountStats {
+    pub label: String,
+    pub mount_point: String,
+    pub device: String,
+    pub fs_type: String,
+    pub mounted: bool, // false for a `require_mount` path that is not mounted
+    pub read_only: bool,
+    pub allow_read_only: bool, // From the mount's config; read-only is then not a problem
+    pub total: u64,
+    pub used: u64,
+    pub free: u64, // Available to unprivileged users
+    pub usage_pct: f64,
+    pub inodes_total: u64,
+    pub inodes_used: u64,
+    pub inodes_free: u64,
+    pub inode_pct: f64,
+    pub warning_pct: f64,
+    pub critical_pct: f64,
+    pub inode_warning_pct: f64,
+    pub inode_critical_pct: f64,
+    pub level: Severity, // Worst of space, inodes and unexpected read-only
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -72,7 +123,33 @@ pub struct CleanupItem {
     pub name: String,
     pub path: String,
     pub size: u64,
-    pub category: String, // "system" | "gemini"
+    pub category: String, // Id of the cleanup provider that found it
+    #[serde(default)]
+    pub reclaim: u64, // Estimated bytes freed; below `size` when the method keeps part of it
+    #[serde(default)]
+    pub risk: CleanupRisk,
+    #[serde(default)]
+    pub method: String, // How the provider frees the space
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CleanupRisk {
+    #[default]
+    Low, // Regenerated on demand (caches, build output)
+    Medium, // Rebuilding costs time or loses non-critical state
+    High, // May hold data that cannot be recreated
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ArtifactDir {
+    pub path: String,
+    pub kind: String,    // "cargo-target" | "node_modules" | "python-venv"
+    pub project: String, // Directory owning the artifacts
+    pub size: u64,
+    pub project_touched: u64, // Newest mtime among the project's own files (Unix seconds)
+    pub idle_days: u64,
+    pub reclaimable: bool, // Project untouched for longer than the stale threshold
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -88,4 +165,47 @@ pub struct DashboardData {
     pub agents: HashMap<String, AgentState>,
     pub all_systems_go: bool,
     pub disk: Option<DiskStats>,
+    #[serde(default)]
+    pub sentry_alerts: Vec<AuditFinding>, // Critical and warning findings from the latest Sentry report
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[default]
+    Ok,
+    Info,
+    Warning,
+    Critical,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AuditFinding {
+    #[serde(default)]
+    pub rule_id: String,
+    pub title: String,
+    pub message: String,
+    pub severity: Severity,
+    #[serde(default)]
+    pub timestamp: String,
+    #[serde(default)]
+    pub paths: Vec<String>, // Workspace-relative paths the finding is about
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AuditRecommendation {
+    #[serde(default)]
+    pub rule_id: String,
+    pub action: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SentryReport {
+    pub id: Option<usize>,
+    pub timestamp: String,
+    pub workspace: String,
+    pub findings: Vec<AuditFinding>,
+    pub recommendations: Vec<AuditRecommendation>,
+    #[serde(default)]
+    pub rules: Vec<String>, // Ids of the rules that ran
 }
@@ -0,0 +1,227 @@
+use std::ffi::CString;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use sysinfo::Disks;
+
+use crate::models::{MountStats, Severity};
+use crate::storage::read_json;
+
+// --- Watched Mounts ---
+//
+// Which filesystems the dashboard and Sentry report on, and when each counts
+// as full. A watched path that is not itself a mount point stands for the
+// filesystem holding it (so `/home` on the root partition is just `/`),
+// unless it is marked `require_mount`, in which case its absence is reported.
+// Byte and inode counts come from statvfs(2); sysinfo only enumerates mounts.
+
+pub const DISK_CONFIG_FILE: &str = "/home/a2/Desktop/gem/opb/backend/disk_config.json";
+
+/// The external drive must be mounted itself; falling back to /home would
+/// report the wrong disk as healthy while it is unplugged.
+fn default_mounts() -> Vec<MountConfig> {
+    [("/", false), ("/home", false), ("/home/a2/mybook", true)].iter()
+        .map(|(path, require_mount)| MountConfig { path: path.to_string(), require_mount: *require_mount, ..MountConfig::default() })
+        .collect()
+}
+
+fn default_warning_pct() -> f64 {
+    85.0
+}
+
+fn default_critical_pct() -> f64 {
+    90.0
+}
+
+fn default_inode_warning_pct() -> f64 {
+    85.0
+}
+
+fn default_inode_critical_pct() -> f64 {
+    95.0
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct MountConfig {
+    pub path: String,
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub require_mount: bool, // Report the mount as missing instead of falling back to its parent filesystem
+    #[serde(default)]
+    pub allow_read_only: bool, // Otherwise a read-only mount is a warning (e.g. remounted after I/O errors)
+    #[serde(default)]
+    pub warning_pct: Option<f64>,
+    #[serde(default)]
+    pub critical_pct: Option<f64>,
+    #[serde(default)]
+    pub inode_warning_pct: Option<f64>,
+    #[serde(default)]
+    pub inode_critical_pct: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiskConfig {
+    #[serde(default = "default_mounts")]
+    pub mounts: Vec<MountConfig>,
+    // Thresholds for mounts that do not set their own
+    #[serde(default = "default_warning_pct")]
+    pub warning_pct: f64,
+    #[serde(default = "default_critical_pct")]
+    pub critical_pct: f64,
+    #[serde(default = "default_inode_warning_pct")]
+    pub inode_warning_pct: f64,
+    #[serde(default = "default_inode_critical_pct")]
+    pub inode_critical_pct: f64,
+}
+
+impl Default for DiskConfig {
+    fn default() -> Self {
+        Self {
+            mounts: default_mounts(),
+            warning_pct: default_warning_pct(),
+            critical_pct: default_critical_pct(),
+            inode_warning_pct: default_inode_warning_pct(),
+            inode_critical_pct: default_inode_critical_pct(),
+        }
+    }
+}
+
+impl DiskConfig {
+    pub fn load() -> Self {
+        read_json(DISK_CONFIG_FILE).unwrap_or_default()
+    }
+}
+
+struct Statvfs {
+    total: u64,
+    free: u64, // Available to unprivileged users
+    bfree: u64, // Free including the blocks reserved for root
+    inodes_total: u64,
+    inodes_free: u64,
+    read_only: bool,
+}
+
+fn statvfs(path: &Path) -> Option<Statvfs> {
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut buf: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut buf) } != 0 {
+        return None;
+    }
+    let frsize = buf.f_frsize as u64;
+    Some(Statvfs {
+        total: buf.f_blocks as u64 * frsize,
+        free: buf.f_bavail as u64 * frsize,
+        bfree: buf.f_bfree as u64 * frsize,
+        inodes_total: buf.f_files as u64,
+        inodes_free: buf.f_ffree as u64,
+        read_only: buf.f_flag & libc::ST_RDONLY != 0,
+    })
+}
+
+/// (total, free) bytes of the filesystem holding `path`.
+pub fn fs_space(path: &Path) -> Option<(u64, u64)> {
+    statvfs(path).map(|vfs| (vfs.total, vfs.free))
+}
+
+pub fn pct(used: u64, total: u64) -> f64 {
+    if total == 0 { 0.0 } else { used as f64 / total as f64 * 100.0 }
+}
+
+pub fn level(value: f64, warning: f64, critical: f64) -> Severity {
+    if value > critical {
+        Severity::Critical
+    } else if value > warning {
+        Severity::Warning
+    } else {
+        Severity::Ok
+    }
+}
+
+/// Stats for every watched mount, in config order. Paths resolving to the same
+/// filesystem are listed once, under the first entry naming it.
+pub fn mount_stats(config: &DiskConfig) -> Vec<MountStats> {
+    let disks = Disks::new_with_refreshed_list();
+    let mut stats: Vec<MountStats> = vec![];
+    for mount in &config.mounts {
+        let path = Path::new(&mount.path);
+        let disk = disks.iter()
+            .filter(|d| path.starts_with(d.mount_point()))
+            .max_by_key(|d| d.mount_point().as_os_str().len());
+        let mounted = disk.is_some_and(|d| d.mount_point() == path);
+        let label = mount.label.clone().unwrap_or_else(|| mount.path.clone());
+
+        let (Some(disk), true) = (disk, mounted || !mount.require_mount) else {
+            stats.push(MountStats { label, mount_point: mount.path.clone(), mounted: false, level: Severity::Warning, ..MountStats::default() });
+            continue;
+        };
+        let mount_point = disk.mount_point().to_string_lossy().to_string();
+        if stats.iter().any(|s| s.mounted && s.mount_point == mount_point) {
+            continue;
+        }
+        let Some(vfs) = statvfs(disk.mount_point()) else { continue };
+
+        // As df: reserved blocks are neither used nor available
+        let used = vfs.total.saturating_sub(vfs.bfree);
+        let inodes_used = vfs.inodes_total.saturating_sub(vfs.inodes_free);
+        let usage_pct = pct(used, used + vfs.free);
+        let inode_pct = pct(inodes_used, vfs.inodes_total); // 0 on filesystems without fixed inode tables (btrfs)
+        let warning_pct = mount.warning_pct.unwrap_or(config.warning_pct);
+        let critical_pct = mount.critical_pct.unwrap_or(config.critical_pct);
+        let inode_warning_pct = mount.inode_warning_pct.unwrap_or(config.inode_warning_pct);
+        let inode_critical_pct = mount.inode_critical_pct.unwrap_or(config.inode_critical_pct);
+        let read_only_level = if vfs.read_only && !mount.allow_read_only { Severity::Warning } else { Severity::Ok };
+
+        stats.push(MountStats {
+            label: if mount.label.is_some() || mounted { label } else { mount_point.clone() },
+            mount_point,
+            device: disk.name().to_string_lossy().into(),
+            fs_type: disk.file_system().to_string_lossy().into(),
+            mounted: true,
+            read_only: vfs.read_only,
+            allow_read_only: mount.allow_read_only,
+            total: vfs.total,
+            used,
+            free: vfs.free,
+            usage_pct,
+            inodes_total: vfs.inodes_total,
+            inodes_used,
+            inodes_free: vfs.inodes_free,
+            inode_pct,
+            warning_pct,
+            critical_pct,
+            inode_warning_pct,
+            inode_critical_pct,
+            level: level(usage_pct, warning_pct, critical_pct)
+                .max(level(inode_pct, inode_warning_pct, inode_critical_pct))
+                .max(read_only_level),
+        });
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_is_ok_up_to_and_including_warning() {
+        assert_eq!(level(0.0, 85.0, 90.0), Severity::Ok);
+        assert_eq!(level(85.0, 85.0, 90.0), Severity::Ok);
+    }
+
+    #[test]
+    fn level_escalates_strictly_above_each_threshold() {
+        assert_eq!(level(85.1, 85.0, 90.0), Severity::Warning);
+        assert_eq!(level(90.0, 85.0, 90.0), Severity::Warning);
+        assert_eq!(level(90.1, 85.0, 90.0), Severity::Critical);
+        assert_eq!(level(100.0, 85.0, 90.0), Severity::Critical);
+    }
+
+    #[test]
+    fn pct_of_an_empty_total_is_zero() {
+        assert_eq!(pct(0, 0), 0.0);
+        assert_eq!(pct(1, 4), 25.0);
+    }
+}
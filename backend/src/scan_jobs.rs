@@ -0,0 +1,280 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::CleanupItem;
+
+// --- Background Scan Jobs ---
+//
+// Filesystem walks (workspace size, cleanup candidates, artifact and disk
+// tree scans) run on blocking threads as jobs with an id, live progress and
+// a cancel flag. A finished job's result is cached under the scan's key, so
+// handlers answer from the cache right away and only start a refresh once it
+// is older than the scan's refresh interval. Starting a scan that is already
+// running returns the running job, unless it was invalidated meanwhile.
+//
+// Walkers report progress through `record`, which reads the job bound to the
+// current thread; outside a job it is a no-op, so the same code serves Sentry
+// and the CLI unchanged.
+
+const MAX_FINISHED_JOBS: usize = 50;
+
+static JOBS: LazyLock<RwLock<JobRegistry>> = LazyLock::new(|| RwLock::new(JobRegistry::default()));
+
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<JobControl>>> = const { RefCell::new(None) };
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScanKind {
+    WorkspaceSize,
+    DirSize { path: String },
+    Cleanup,
+    Artifacts { stale_days: u64 },
+    DiskTree { path: String },
+}
+
+impl ScanKind {
+    /// Cache key; scans with equal keys share a job and a result.
+    pub fn key(&self) -> String {
+        match self {
+            ScanKind::WorkspaceSize => "workspace_size".into(),
+            ScanKind::DirSize { path } => format!("dir_size:{}", path),
+            ScanKind::Cleanup => "cleanup".into(),
+            ScanKind::Artifacts { stale_days } => format!("artifacts:{}", stale_days),
+            ScanKind::DiskTree { path } => format!("disk_tree:{}", path),
+        }
+    }
+
+    /// Seconds a cached result is served before a request triggers a refresh.
+    pub fn refresh_after(&self) -> u64 {
+        match self {
+            ScanKind::WorkspaceSize | ScanKind::DirSize { .. } => 60,
+            ScanKind::Cleanup | ScanKind::Artifacts { .. } => 300,
+            ScanKind::DiskTree { .. } => 0, // The tree tracks its own staleness
+        }
+    }
+
+    fn run(&self) -> serde_json::Value {
+        match self {
+            ScanKind::WorkspaceSize => serde_json::json!(crate::storage::calculate_dir_size(crate::cleanup::WORKSPACE)),
+            ScanKind::DirSize { path } => serde_json::json!(crate::storage::calculate_dir_size(path)),
+            ScanKind::Cleanup => {
+                let reports = crate::cleanup::scan_providers(&crate::cleanup::CleanupConfig::load());
+                serde_json::to_value(reports).unwrap_or_default()
+            }
+            ScanKind::Artifacts { stale_days } => {
+                let dirs = crate::artifacts::scan_artifacts(std::path::Path::new(crate::cleanup::WORKSPACE), *stale_days);
+                serde_json::to_value(dirs).unwrap_or_default()
+            }
+            ScanKind::DiskTree { path } => {
+                crate::disk_tree::scan(&PathBuf::from(path));
+                serde_json::Value::Null // Merged into the tree cache
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Running,
+    Done,
+    Cancelled,
+    Failed,
+}
+
+#[derive(Default)]
+struct JobControl {
+    cancel: AtomicBool,
+    files: AtomicU64,
+    bytes: AtomicU64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ScanJob {
+    pub id: String,
+    #[serde(flatten)]
+    pub kind: ScanKind,
+    pub status: JobStatus,
+    pub cancel_requested: bool,
+    pub files_seen: u64,
+    pub bytes_seen: u64,
+    pub started_at: u64, // Unix seconds
+    pub finished_at: Option<u64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CachedScan {
+    pub job_id: String,
+    pub finished_at: u64,
+    pub value: serde_json::Value,
+}
+
+#[derive(Default)]
+struct JobRegistry {
+    jobs: HashMap<String, (ScanJob, Arc<JobControl>)>,
+    finished: VecDeque<String>, // Oldest first; trimmed to MAX_FINISHED_JOBS
+    results: HashMap<String, CachedScan>,
+    next_id: u64,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// Counts `files` and `bytes` towards the current job. Returns false once the
+/// job was cancelled, telling the walker to stop.
+pub fn record(files: u64, bytes: u64) -> bool {
+    CURRENT.with(|current| match &*current.borrow() {
+        Some(control) => {
+            control.files.fetch_add(files, Ordering::Relaxed);
+            control.bytes.fetch_add(bytes, Ordering::Relaxed);
+            !control.cancel.load(Ordering::Relaxed)
+        }
+        None => true,
+    })
+}
+
+/// Whether the job running on this thread was cancelled.
+pub fn cancelled() -> bool {
+    CURRENT.with(|current| current.borrow().as_ref().is_some_and(|c| c.cancel.load(Ordering::Relaxed)))
+}
+
+fn snapshot(job: &ScanJob, control: &JobControl) -> ScanJob {
+    ScanJob {
+        cancel_requested: control.cancel.load(Ordering::Relaxed),
+        files_seen: control.files.load(Ordering::Relaxed),
+        bytes_seen: control.bytes.load(Ordering::Relaxed),
+        ..job.clone()
+    }
+}
+
+fn finish(id: &str, key: &str, outcome: std::thread::Result<serde_json::Value>) {
+    let mut registry = JOBS.write().unwrap_or_else(|e| e.into_inner());
+    let Some((job, control)) = registry.jobs.get(id).cloned() else { return };
+    let mut job = snapshot(&job, &control);
+    job.finished_at = Some(now_secs());
+    match outcome {
+        Ok(_) if job.cancel_requested => job.status = JobStatus::Cancelled,
+        Ok(value) => {
+            job.status = JobStatus::Done;
+            registry.results.insert(key.to_string(), CachedScan { job_id: id.to_string(), finished_at: now_secs(), value });
+        }
+        Err(_) => {
+            job.status = JobStatus::Failed;
+            job.error = Some("Scan panicked".into());
+        }
+    }
+    registry.jobs.insert(id.to_string(), (job, control));
+    registry.finished.push_back(id.to_string());
+    while registry.finished.len() > MAX_FINISHED_JOBS {
+        if let Some(old) = registry.finished.pop_front() {
+            registry.jobs.remove(&old);
+        }
+    }
+}
+
+/// Starts `kind` in the background, or returns the job already scanning it.
+/// Jobs asked to cancel are not reused; their results are never cached.
+pub fn start(kind: ScanKind) -> ScanJob {
+    let key = kind.key();
+    let mut registry = JOBS.write().unwrap_or_else(|e| e.into_inner());
+    if let Some((job, control)) = registry.jobs.values()
+        .find(|(j, c)| j.status == JobStatus::Running && j.kind.key() == key && !c.cancel.load(Ordering::Relaxed))
+    {
+        return snapshot(job, control);
+    }
+
+    registry.next_id += 1;
+    let id = format!("scan-{}-{}", now_secs(), registry.next_id);
+    let job = ScanJob {
+        id: id.clone(),
+        kind: kind.clone(),
+        status: JobStatus::Running,
+        cancel_requested: false,
+        files_seen: 0,
+        bytes_seen: 0,
+        started_at: now_secs(),
+        finished_at: None,
+        error: None,
+    };
+    let control = Arc::new(JobControl::default());
+    registry.jobs.insert(id.clone(), (job.clone(), control.clone()));
+    drop(registry);
+
+    tokio::task::spawn_blocking(move || {
+        CURRENT.with(|current| *current.borrow_mut() = Some(control));
+        let outcome = std::panic::catch_unwind(|| kind.run());
+        CURRENT.with(|current| *current.borrow_mut() = None);
+        finish(&id, &key, outcome);
+    });
+    job
+}
+
+/// The cached result of `kind`, starting a refresh when it is missing or
+/// older than the scan's refresh interval. The job is returned when one runs.
+pub fn cached_or_refresh(kind: ScanKind) -> (Option<CachedScan>, Option<ScanJob>) {
+    let cached = JOBS.read().unwrap_or_else(|e| e.into_inner()).results.get(&kind.key()).cloned();
+    let fresh = cached.as_ref().is_some_and(|c| now_secs().saturating_sub(c.finished_at) < kind.refresh_after());
+    let job = (!fresh).then(|| start(kind));
+    (cached, job)
+}
+
+/// Drops the cached result of `kind` and rescans it, for when the files it
+/// covers were just changed (e.g. by a cleanup) and the old result is wrong.
+/// A scan of `kind` already running started before the change, so it is
+/// cancelled rather than reused.
+pub fn invalidate(kind: ScanKind) -> ScanJob {
+    let key = kind.key();
+    let mut registry = JOBS.write().unwrap_or_else(|e| e.into_inner());
+    registry.results.remove(&key);
+    for (job, control) in registry.jobs.values() {
+        if job.status == JobStatus::Running && job.kind.key() == key {
+            control.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+    drop(registry);
+    start(kind)
+}
+
+pub fn get(id: &str) -> Option<ScanJob> {
+    JOBS.read().unwrap_or_else(|e| e.into_inner()).jobs.get(id).map(|(job, control)| snapshot(job, control))
+}
+
+/// Every known job, newest first.
+pub fn list() -> Vec<ScanJob> {
+    let mut jobs: Vec<ScanJob> = JOBS.read().unwrap_or_else(|e| e.into_inner()).jobs.values()
+        .map(|(job, control)| snapshot(job, control))
+        .collect();
+    jobs.sort_by_key(|j| std::cmp::Reverse((j.started_at, j.id.len(), j.id.clone())));
+    jobs
+}
+
+/// Asks a running job to stop; walkers notice at their next `record`.
+pub fn cancel(id: &str) -> Option<ScanJob> {
+    let registry = JOBS.read().unwrap_or_else(|e| e.into_inner());
+    let (job, control) = registry.jobs.get(id)?;
+    if job.status == JobStatus::Running {
+        control.cancel.store(true, Ordering::Relaxed);
+    }
+    Some(snapshot(job, control))
+}
+
+/// Cleanup candidates from a cached provider scan, largest reclaim first.
+pub fn cleanup_candidates(cached: &CachedScan) -> Vec<CleanupItem> {
+    let mut all: Vec<CleanupItem> = cached.value.as_array().into_iter().flatten()
+        .filter_map(|report| report.get("candidates"))
+        .filter_map(|c| serde_json::from_value::<Vec<CleanupItem>>(c.clone()).ok())
+        .flatten()
+        .collect();
+    all.sort_by_key(|c| std::cmp::Reverse(c.reclaim));
+    all
+}
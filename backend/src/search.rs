@@ -0,0 +1,492 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::sync::{LazyLock, Mutex, RwLock};
+
+use chrono::{DateTime, Local};
+use serde::Serialize;
+
+use crate::models::{DocInfo, Message};
+use crate::storage::read_json;
+
+// --- Full-Text Search ---
+//
+// An in-memory inverted index over the live conversation log, archived
+// fragments and registered docs. Entries are grouped by `source` (the file
+// they came from) so any one file can be re-indexed without a full rebuild.
+// Updates made while a rebuild runs go to the live index and are also queued,
+// then replayed onto the rebuilt index before it replaces the live one.
+
+const LOG_FILE: &str = "conversation_log.json";
+const DOCS_FILE: &str = "docs.json";
+const FRAGMENTS_DIR: &str = "backend/fragments";
+const MAX_INDEXED_DOC: u64 = 8 * 1024 * 1024;
+const SNIPPET_RADIUS: usize = 80;
+const MIN_COMPACT_ENTRIES: usize = 1024; // Smaller indexes keep their tombstones
+
+static INDEX: LazyLock<RwLock<SearchIndex>> = LazyLock::new(|| RwLock::new(SearchIndex::default()));
+static PENDING: Mutex<Option<Vec<Update>>> = Mutex::new(None); // Some while a rebuild runs
+
+/// An incremental change, kept for replay onto an index still being rebuilt.
+enum Update {
+    Log, // The live log changed; it is re-read rather than appended to, to avoid duplicates
+    Source(String, Vec<Entry>),
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    kind: &'static str, // "message" | "fragment" | "doc"
+    source: String,
+    message_id: Option<usize>,
+    sender: Option<String>,
+    category: Option<String>,
+    timestamp: Option<String>,
+    text: String,
+    tokens: usize,
+    terms: Vec<String>, // Distinct terms, so removal only touches this entry's postings
+}
+
+#[derive(Default)]
+pub struct SearchIndex {
+    entries: Vec<Option<Entry>>,
+    postings: HashMap<String, HashMap<usize, Vec<u32>>>, // term -> entry -> positions
+    by_source: HashMap<String, Vec<usize>>,
+    live: usize,
+    total_tokens: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct SearchQuery {
+    pub text: String,
+    pub sender: Option<String>,
+    pub category: Option<String>,
+    pub kind: Option<String>,
+    pub from: Option<String>, // inclusive date/time prefix, e.g. "2026-01-17"
+    pub to: Option<String>,
+    pub limit: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchHit {
+    pub kind: String,
+    pub source: String,
+    pub message_id: Option<usize>,
+    pub sender: Option<String>,
+    pub category: Option<String>,
+    pub timestamp: Option<String>,
+    pub score: f64,
+    pub snippet: String,
+}
+
+fn tokenize(text: &str) -> Vec<(usize, usize, String)> {
+    let mut tokens = vec![];
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                tokens.push((s, i, text[s..i].to_lowercase()));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        tokens.push((s, text.len(), text[s..].to_lowercase()));
+    }
+    tokens
+}
+
+/// Splits a raw query into individual terms and quoted phrases.
+fn parse_query(q: &str) -> (Vec<String>, Vec<Vec<String>>) {
+    let mut terms = vec![];
+    let mut phrases = vec![];
+    for (i, part) in q.split('"').enumerate() {
+        let words: Vec<String> = tokenize(part).into_iter().map(|(_, _, t)| t).collect();
+        if i % 2 == 1 && words.len() > 1 {
+            phrases.push(words.clone());
+        }
+        terms.extend(words);
+    }
+    terms.sort();
+    terms.dedup();
+    (terms, phrases)
+}
+
+impl SearchIndex {
+    fn add(&mut self, entry: Entry) {
+        let id = self.entries.len();
+        let tokens = tokenize(&entry.text);
+        for (pos, (_, _, term)) in tokens.iter().enumerate() {
+            self.postings.entry(term.clone()).or_default().entry(id).or_default().push(pos as u32);
+        }
+        let mut terms: Vec<String> = tokens.iter().map(|(_, _, t)| t.clone()).collect();
+        terms.sort();
+        terms.dedup();
+        self.by_source.entry(entry.source.clone()).or_default().push(id);
+        self.total_tokens += tokens.len();
+        self.live += 1;
+        self.entries.push(Some(Entry { tokens: tokens.len(), terms, ..entry }));
+    }
+
+    fn remove_source(&mut self, source: &str) {
+        let Some(ids) = self.by_source.remove(source) else { return };
+        for id in ids {
+            let Some(entry) = self.entries[id].take() else { continue };
+            self.total_tokens -= entry.tokens;
+            self.live -= 1;
+            for term in &entry.terms {
+                if let Some(docs) = self.postings.get_mut(term) {
+                    docs.remove(&id);
+                    if docs.is_empty() {
+                        self.postings.remove(term);
+                    }
+                }
+            }
+        }
+        if self.entries.len() >= MIN_COMPACT_ENTRIES && self.live < self.entries.len() / 2 {
+            self.compact();
+        }
+    }
+
+    /// Renumbers the live entries, dropping the tombstones left by removals.
+    fn compact(&mut self) {
+        let entries = std::mem::take(&mut self.entries);
+        *self = SearchIndex::default();
+        for entry in entries.into_iter().flatten() {
+            self.add(entry);
+        }
+    }
+
+    fn replace_source(&mut self, source: &str, entries: Vec<Entry>) {
+        self.remove_source(source);
+        for entry in entries {
+            self.add(entry);
+        }
+    }
+
+    fn matches_phrase(&self, id: usize, phrase: &[String]) -> bool {
+        let Some(first) = self.postings.get(&phrase[0]).and_then(|d| d.get(&id)) else { return false };
+        first.iter().any(|&start| {
+            phrase.iter().enumerate().skip(1).all(|(offset, term)| {
+                self.postings.get(term)
+                    .and_then(|d| d.get(&id))
+                    .is_some_and(|positions| positions.contains(&(start + offset as u32)))
+            })
+        })
+    }
+
+    fn passes_filters(entry: &Entry, q: &SearchQuery) -> bool {
+        let eq = |field: &Option<String>, want: &Option<String>| match want {
+            Some(w) => field.as_deref().is_some_and(|f| f.eq_ignore_ascii_case(w)),
+            None => true,
+        };
+        let ts = entry.timestamp.as_deref().unwrap_or("");
+        eq(&entry.sender, &q.sender)
+            && eq(&entry.category, &q.category)
+            && q.kind.as_deref().is_none_or(|k| k == entry.kind)
+            && q.from.as_deref().is_none_or(|from| ts >= from)
+            && q.to.as_deref().is_none_or(|to| ts.get(..to.len()).is_some_and(|p| p <= to))
+    }
+
+    /// Ranks entries containing every query term with BM25.
+    pub fn search(&self, q: &SearchQuery) -> (usize, Vec<SearchHit>) {
+        let (terms, phrases) = parse_query(&q.text);
+        if terms.is_empty() {
+            return (0, vec![]);
+        }
+
+        let Some(mut candidates) = terms.iter()
+            .map(|t| self.postings.get(t).map(|d| d.keys().copied().collect::<HashSet<_>>()).unwrap_or_default())
+            .reduce(|a, b| a.intersection(&b).copied().collect())
+        else {
+            return (0, vec![]);
+        };
+        candidates.retain(|id| phrases.iter().all(|p| self.matches_phrase(*id, p)));
+
+        let n = self.live.max(1) as f64;
+        let avg_len = self.total_tokens as f64 / n;
+        let (k1, b) = (1.2, 0.75);
+
+        let mut scored: Vec<(f64, usize)> = candidates.into_iter()
+            .filter_map(|id| self.entries[id].as_ref().map(|e| (id, e)))
+            .filter(|(_, e)| Self::passes_filters(e, q))
+            .map(|(id, entry)| {
+                let len = entry.tokens.max(1) as f64;
+                let score = terms.iter().map(|t| {
+                    let docs = &self.postings[t];
+                    let tf = docs[&id].len() as f64;
+                    let idf = ((n - docs.len() as f64 + 0.5) / (docs.len() as f64 + 0.5) + 1.0).ln();
+                    idf * tf * (k1 + 1.0) / (tf + k1 * (1.0 - b + b * len / avg_len))
+                }).sum::<f64>();
+                (score + phrases.len() as f64, id)
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let total = scored.len();
+
+        let hits = scored.into_iter().take(q.limit).filter_map(|(score, id)| {
+            let e = self.entries[id].as_ref()?;
+            Some(SearchHit {
+                kind: e.kind.to_string(),
+                source: e.source.clone(),
+                message_id: e.message_id,
+                sender: e.sender.clone(),
+                category: e.category.clone(),
+                timestamp: e.timestamp.clone(),
+                score,
+                snippet: snippet(&e.text, &terms),
+            })
+        }).collect();
+
+        (total, hits)
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Extracts a window around the first matching term, wrapping every match in
+/// `<mark>`. The text itself is HTML-escaped, so the result is safe to render.
+fn snippet(text: &str, terms: &[String]) -> String {
+    let tokens = tokenize(text);
+    let matched: Vec<&(usize, usize, String)> = tokens.iter().filter(|(_, _, t)| terms.contains(t)).collect();
+    let Some(first) = matched.first() else {
+        return escape_html(&text.chars().take(SNIPPET_RADIUS * 2).collect::<String>()).replace('\n', " ");
+    };
+
+    let floor = |mut i: usize| { while !text.is_char_boundary(i) { i -= 1; } i };
+    let start = floor(first.0.saturating_sub(SNIPPET_RADIUS));
+    let end = floor((first.1 + SNIPPET_RADIUS).min(text.len()));
+
+    let mut out = String::new();
+    if start > 0 { out.push('…'); }
+    let mut cursor = start;
+    for (s, e, _) in matched.iter().filter(|(s, e, _)| *s >= start && *e <= end) {
+        out.push_str(&escape_html(&text[cursor..*s]));
+        out.push_str("<mark>");
+        out.push_str(&escape_html(&text[*s..*e]));
+        out.push_str("</mark>");
+        cursor = *e;
+    }
+    out.push_str(&escape_html(&text[cursor..end]));
+    if end < text.len() { out.push('…'); }
+    out.replace('\n', " ")
+}
+
+// --- Indexing ---
+
+fn message_entries(messages: &[Message], kind: &'static str, source: &str, category: Option<String>) -> Vec<Entry> {
+    messages.iter().map(|m| Entry {
+        kind,
+        source: source.to_string(),
+        message_id: m.id,
+        sender: Some(m.sender.clone()),
+        category: category.clone(),
+        timestamp: m.timestamp.clone(),
+        text: m.message.clone(),
+        tokens: 0,
+        terms: vec![],
+    }).collect()
+}
+
+fn doc_entries(doc: &DocInfo) -> Vec<Entry> {
+    if doc.is_fragment.unwrap_or(false) {
+        let messages: Vec<Message> = read_json(&doc.path).unwrap_or_default();
+        return message_entries(&messages, "fragment", &doc.path, doc.category.clone());
+    }
+
+    let Ok(metadata) = fs::metadata(&doc.path) else { return vec![] };
+    if !metadata.is_file() || metadata.len() > MAX_INDEXED_DOC {
+        return vec![];
+    }
+    let Ok(text) = fs::read_to_string(&doc.path) else { return vec![] };
+
+    let mut doc = doc.clone();
+    crate::doc_meta::refresh(&mut doc);
+
+    let timestamp = metadata.modified().ok().map(|t| DateTime::<Local>::from(t).to_rfc3339());
+    vec![Entry {
+        kind: "doc",
+        source: doc.path.clone(),
+        message_id: None,
+        sender: doc.owner,
+        category: doc.category,
+        timestamp,
+        text,
+        tokens: 0,
+        terms: vec![],
+    }]
+}
+
+/// Queues `update` if a rebuild is running. Called with the index write lock
+/// held, which `rebuild` also takes before draining the queue.
+fn queue(update: Update) {
+    if let Some(pending) = PENDING.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        pending.push(update);
+    }
+}
+
+fn replace_source(source: &str, entries: Vec<Entry>) {
+    let mut index = INDEX.write().unwrap_or_else(|e| e.into_inner());
+    index.replace_source(source, entries.clone());
+    queue(Update::Source(source.to_string(), entries));
+}
+
+/// Adds a freshly posted message from the live conversation log.
+pub fn index_message(msg: &Message) {
+    let entries = message_entries(std::slice::from_ref(msg), "message", LOG_FILE, None);
+    let mut index = INDEX.write().unwrap_or_else(|e| e.into_inner());
+    for entry in entries {
+        index.add(entry);
+    }
+    queue(Update::Log);
+}
+
+/// Re-reads the live conversation log (e.g. after it was fragmented and cleared).
+pub fn reindex_log() {
+    let messages: Vec<Message> = read_json(LOG_FILE).unwrap_or_default();
+    replace_source(LOG_FILE, message_entries(&messages, "message", LOG_FILE, None));
+}
+
+pub fn index_doc(doc: &DocInfo) {
+    replace_source(&doc.path, doc_entries(doc));
+}
+
+pub fn remove_doc(path: &str) {
+    replace_source(path, vec![]);
+}
+
+/// Rebuilds the whole index from disk. Fragments on disk that were never
+/// registered as docs are included too.
+pub fn rebuild() {
+    *PENDING.lock().unwrap_or_else(|e| e.into_inner()) = Some(vec![]);
+    let docs: Vec<DocInfo> = read_json(DOCS_FILE).unwrap_or_default();
+    let mut fresh = SearchIndex::default();
+
+    let messages: Vec<Message> = read_json(LOG_FILE).unwrap_or_default();
+    message_entries(&messages, "message", LOG_FILE, None).into_iter().for_each(|e| fresh.add(e));
+
+    for doc in &docs {
+        doc_entries(doc).into_iter().for_each(|e| fresh.add(e));
+    }
+
+    if let Ok(dir) = fs::read_dir(FRAGMENTS_DIR) {
+        for entry in dir.flatten() {
+            let path = entry.path().to_string_lossy().to_string();
+            if Path::new(&path).extension().is_some_and(|e| e == "json") && !docs.iter().any(|d| d.path == path) {
+                let orphan = DocInfo { path, is_fragment: Some(true), ..Default::default() };
+                doc_entries(&orphan).into_iter().for_each(|e| fresh.add(e));
+            }
+        }
+    }
+
+    let mut index = INDEX.write().unwrap_or_else(|e| e.into_inner());
+    let pending = PENDING.lock().unwrap_or_else(|e| e.into_inner()).take().unwrap_or_default();
+    let mut log_changed = false;
+    for update in pending {
+        match update {
+            Update::Log => log_changed = true,
+            Update::Source(source, entries) => fresh.replace_source(&source, entries),
+        }
+    }
+    if log_changed {
+        let messages: Vec<Message> = read_json(LOG_FILE).unwrap_or_default();
+        fresh.replace_source(LOG_FILE, message_entries(&messages, "message", LOG_FILE, None));
+    }
+    *index = fresh;
+}
+
+pub fn search(q: &SearchQuery) -> (usize, Vec<SearchHit>) {
+    INDEX.read().unwrap_or_else(|e| e.into_inner()).search(q)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(source: &str, text: &str) -> Entry {
+        Entry {
+            kind: "doc",
+            source: source.to_string(),
+            message_id: None,
+            sender: None,
+            category: None,
+            timestamp: None,
+            text: text.to_string(),
+            tokens: 0,
+            terms: vec![],
+        }
+    }
+
+    fn query(text: &str) -> SearchQuery {
+        SearchQuery { text: text.to_string(), limit: 10, ..Default::default() }
+    }
+
+    fn sources(index: &SearchIndex, text: &str) -> Vec<String> {
+        index.search(&query(text)).1.into_iter().map(|h| h.source).collect()
+    }
+
+    #[test]
+    fn tokenize_lowercases_and_splits_on_non_alphanumerics() {
+        let terms: Vec<String> = tokenize("Deploy-v2, NOW! über").into_iter().map(|(_, _, t)| t).collect();
+        assert_eq!(terms, ["deploy", "v2", "now", "über"]);
+        assert_eq!(tokenize("ab cd")[1], (3, 5, "cd".to_string()));
+    }
+
+    #[test]
+    fn query_requires_every_term_and_honours_phrases() {
+        let mut index = SearchIndex::default();
+        index.add(doc("a", "the rocket launch is delayed"));
+        index.add(doc("b", "launch the rocket tomorrow"));
+        index.add(doc("c", "rocket fuel only"));
+
+        let mut both = sources(&index, "rocket launch");
+        both.sort();
+        assert_eq!(both, ["a", "b"]);
+        assert_eq!(sources(&index, "\"rocket launch\""), ["a"]);
+        assert!(sources(&index, "missing").is_empty());
+    }
+
+    #[test]
+    fn remove_source_drops_its_postings() {
+        let mut index = SearchIndex::default();
+        index.add(doc("a", "alpha shared"));
+        index.add(doc("b", "beta shared"));
+        index.remove_source("a");
+
+        assert!(sources(&index, "alpha").is_empty());
+        assert_eq!(sources(&index, "shared"), ["b"]);
+        assert!(!index.postings.contains_key("alpha"));
+        assert_eq!(index.live, 1);
+    }
+
+    #[test]
+    fn replacing_sources_compacts_tombstones() {
+        let mut index = SearchIndex::default();
+        for i in 0..MIN_COMPACT_ENTRIES + 10 {
+            index.replace_source("doc", vec![doc("doc", &format!("revision {}", i))]);
+        }
+        assert!(index.entries.len() < MIN_COMPACT_ENTRIES);
+        assert_eq!(sources(&index, "revision"), ["doc"]);
+        assert_eq!(index.by_source["doc"].len(), 1);
+    }
+
+    #[test]
+    fn snippet_escapes_html_around_marks() {
+        let text = "<img src=x onerror=alert(1)> deploy <b>now</b>";
+        let out = snippet(text, &["deploy".to_string()]);
+        assert_eq!(out, "&lt;img src=x onerror=alert(1)&gt; <mark>deploy</mark> &lt;b&gt;now&lt;/b&gt;");
+    }
+
+    #[test]
+    fn snippet_without_match_is_escaped_too() {
+        assert_eq!(snippet("<script>x</script>", &["other".to_string()]), "&lt;script&gt;x&lt;/script&gt;");
+    }
+}
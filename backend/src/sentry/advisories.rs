@@ -0,0 +1,352 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use semver::{Comparator, Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::sentry::Severity;
+
+// --- Offline Advisory Database ---
+//
+// Reads a local checkout of the RustSec `advisory-db` (as fetched by
+// `cargo audit`) and a JSON dump of npm advisories. Nothing is downloaded;
+// keeping the mirror fresh is up to the user.
+//
+// The npm dump may be either the bulk format (`{"<package>": [advisory, ...]}`)
+// or the `npm audit` format (`{"advisories": {"<id>": advisory}}`).
+
+pub const DEFAULT_RUSTSEC_DIR: &str = "/home/a2/.cargo/advisory-db";
+pub const DEFAULT_NPM_DB: &str = "/home/a2/.local/share/npm-advisories.json";
+
+fn default_rustsec_dir() -> String {
+    DEFAULT_RUSTSEC_DIR.to_string()
+}
+
+fn default_npm_db() -> String {
+    DEFAULT_NPM_DB.to_string()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AdvisoryConfig {
+    #[serde(default = "default_rustsec_dir")]
+    pub rustsec_dir: String,
+    #[serde(default = "default_npm_db")]
+    pub npm_db: String,
+}
+
+impl Default for AdvisoryConfig {
+    fn default() -> Self {
+        Self { rustsec_dir: default_rustsec_dir(), npm_db: default_npm_db() }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Ecosystem {
+    Cargo,
+    Npm,
+}
+
+#[derive(Debug, Clone)]
+enum Affected {
+    /// RustSec: vulnerable unless matched by a patched or unaffected requirement
+    Unless(Vec<VersionReq>),
+    /// npm: vulnerable when matched by the range
+    Range(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct Advisory {
+    pub id: String,
+    pub aliases: Vec<String>,
+    pub title: String,
+    pub severity: Severity,
+    pub url: Option<String>,
+    pub fixed: Option<String>, // Human-readable fixed versions, when there are any
+    affected: Affected,
+}
+
+impl Advisory {
+    /// Like `VersionReq`, a prerelease is only judged by ranges that name a
+    /// prerelease of the same major.minor.patch. Otherwise `2.0.0-rc.1` would
+    /// fail every `>=1.5.0` patched requirement and always look vulnerable.
+    pub fn affects(&self, version: &Version) -> bool {
+        if !version.pre.is_empty() && !self.mentions_prerelease_of(version) {
+            return false;
+        }
+        match &self.affected {
+            Affected::Unless(safe) => !safe.iter().any(|req| req.matches(version)),
+            Affected::Range(range) => npm_range_matches(range, version),
+        }
+    }
+
+    fn mentions_prerelease_of(&self, version: &Version) -> bool {
+        let same = |pre: bool, major: u64, minor: Option<u64>, patch: Option<u64>| {
+            pre && major == version.major && minor == Some(version.minor) && patch == Some(version.patch)
+        };
+        match &self.affected {
+            Affected::Unless(safe) => safe.iter()
+                .flat_map(|req| &req.comparators)
+                .any(|c| same(!c.pre.is_empty(), c.major, c.minor, c.patch)),
+            Affected::Range(range) => range.split(|c: char| c.is_whitespace() || c == '|')
+                .map(|token| token.trim_start_matches(|c| "<>=~^v".contains(c)))
+                .filter_map(|token| Version::parse(token).ok())
+                .any(|v| same(!v.pre.is_empty(), v.major, Some(v.minor), Some(v.patch))),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct AdvisoryDb {
+    advisories: HashMap<(Ecosystem, String), Vec<Advisory>>,
+    pub sources: Vec<String>, // Mirrors that could be read
+}
+
+impl AdvisoryDb {
+    pub fn load(config: &AdvisoryConfig) -> Self {
+        let mut db = Self::default();
+        let rustsec = Path::new(&config.rustsec_dir);
+        if rustsec.is_dir() {
+            db.load_rustsec(rustsec);
+            db.sources.push(config.rustsec_dir.clone());
+        }
+        if let Ok(content) = fs::read_to_string(&config.npm_db) {
+            match serde_json::from_str(&content) {
+                Ok(json) => {
+                    db.load_npm(&json);
+                    db.sources.push(config.npm_db.clone());
+                }
+                Err(e) => eprintln!("⚠️ Invalid npm advisory dump {}: {}", config.npm_db, e),
+            }
+        }
+        db
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.advisories.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.advisories.values().map(|a| a.len()).sum()
+    }
+
+    /// Advisories affecting `name` at `version`. Unparseable versions match nothing.
+    pub fn matching(&self, ecosystem: Ecosystem, name: &str, version: &str) -> Vec<&Advisory> {
+        let Ok(version) = Version::parse(version.trim_start_matches('v')) else {
+            return vec![];
+        };
+        self.advisories.get(&(ecosystem, name.to_string()))
+            .map(|list| list.iter().filter(|a| a.affects(&version)).collect())
+            .unwrap_or_default()
+    }
+
+    fn insert(&mut self, ecosystem: Ecosystem, package: String, advisory: Advisory) {
+        self.advisories.entry((ecosystem, package)).or_default().push(advisory);
+    }
+
+    fn load_rustsec(&mut self, dir: &Path) {
+        for entry in WalkDir::new(dir.join("crates")).into_iter().flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|e| e != "md" && e != "toml") {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(path) else { continue };
+            if let Some((package, advisory)) = parse_rustsec(&content) {
+                self.insert(Ecosystem::Cargo, package, advisory);
+            }
+        }
+    }
+
+    fn load_npm(&mut self, json: &serde_json::Value) {
+        let Some(root) = json.as_object() else { return };
+        let entries: Vec<(Option<&str>, &serde_json::Value)> = match root.get("advisories").and_then(|a| a.as_object()) {
+            Some(advisories) => advisories.values().map(|a| (None, a)).collect(),
+            None => root.iter()
+                .filter_map(|(name, list)| list.as_array().map(|l| (name, l)))
+                .flat_map(|(name, list)| list.iter().map(move |a| (Some(name.as_str()), a)))
+                .collect(),
+        };
+        for (name, raw) in entries {
+            let Ok(a) = serde_json::from_value::<NpmAdvisory>(raw.clone()) else { continue };
+            let Some(package) = name.map(str::to_string).or(a.module_name) else { continue };
+            let id = match (&a.github_advisory_id, &a.id) {
+                (Some(ghsa), _) => ghsa.clone(),
+                (None, Some(serde_json::Value::Number(n))) => format!("NPM-{}", n),
+                (None, Some(serde_json::Value::String(id))) => id.clone(),
+                _ => continue,
+            };
+            let advisory = Advisory {
+                id,
+                aliases: a.cves,
+                title: a.title.unwrap_or_default(),
+                severity: match a.severity.as_deref() {
+                    Some("critical") | Some("high") => Severity::Critical,
+                    Some("low") | Some("info") => Severity::Info,
+                    _ => Severity::Warning,
+                },
+                url: a.url,
+                fixed: a.patched_versions.filter(|p| !p.is_empty() && p != "<0.0.0"),
+                affected: Affected::Range(a.vulnerable_versions),
+            };
+            self.insert(Ecosystem::Npm, package, advisory);
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RustSecFile {
+    advisory: RustSecAdvisory,
+    #[serde(default)]
+    versions: RustSecVersions,
+}
+
+#[derive(Debug, Deserialize)]
+struct RustSecAdvisory {
+    id: String,
+    package: String,
+    title: Option<String>, // Older advisories; newer ones use the Markdown heading
+    #[serde(default)]
+    aliases: Vec<String>,
+    url: Option<String>,
+    informational: Option<String>,
+    withdrawn: Option<toml::Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RustSecVersions {
+    #[serde(default)]
+    patched: Vec<String>,
+    #[serde(default)]
+    unaffected: Vec<String>,
+}
+
+/// Parses a RustSec advisory, either Markdown with a TOML front matter block or plain TOML.
+fn parse_rustsec(content: &str) -> Option<(String, Advisory)> {
+    let (front, body) = match content.trim_start().strip_prefix("```toml") {
+        Some(rest) => rest.split_once("\n```")?,
+        None => (content, ""),
+    };
+    let file: RustSecFile = toml::from_str(front).ok()?;
+    if file.advisory.withdrawn.is_some() {
+        return None;
+    }
+    let heading = body.lines().find_map(|l| l.strip_prefix("# ")).map(|t| t.trim().to_string());
+    let safe: Vec<VersionReq> = file.versions.patched.iter()
+        .chain(&file.versions.unaffected)
+        .filter_map(|r| VersionReq::parse(r).ok())
+        .collect();
+    let fixed = (!file.versions.patched.is_empty()).then(|| file.versions.patched.join(" or "));
+    let advisory = Advisory {
+        id: file.advisory.id,
+        aliases: file.advisory.aliases,
+        title: file.advisory.title.or(heading).unwrap_or_default(),
+        severity: if file.advisory.informational.is_some() { Severity::Info } else { Severity::Warning },
+        url: file.advisory.url,
+        fixed,
+        affected: Affected::Unless(safe),
+    };
+    Some((file.advisory.package, advisory))
+}
+
+#[derive(Debug, Deserialize)]
+struct NpmAdvisory {
+    id: Option<serde_json::Value>,
+    github_advisory_id: Option<String>,
+    module_name: Option<String>,
+    title: Option<String>,
+    severity: Option<String>,
+    url: Option<String>,
+    vulnerable_versions: String,
+    patched_versions: Option<String>,
+    #[serde(default)]
+    cves: Vec<String>,
+}
+
+/// node-semver range: `||`-separated sets of space-separated comparators, with hyphen ranges.
+pub fn npm_range_matches(range: &str, version: &Version) -> bool {
+    range.split("||").any(|set| {
+        let set = set.trim();
+        if let Some((lo, hi)) = set.split_once(" - ") {
+            return [format!(">={}", lo.trim()), format!("<={}", hi.trim())].iter()
+                .all(|c| comparator_matches(c, version));
+        }
+        // Re-attach operators written apart from their version (`>= 1.2.3`)
+        let mut comparators: Vec<String> = vec![];
+        for token in set.split_whitespace() {
+            match comparators.last_mut() {
+                Some(last) if last.chars().all(|c| "<>=~^".contains(c)) => last.push_str(token),
+                _ => comparators.push(token.to_string()),
+            }
+        }
+        comparators.iter().all(|c| comparator_matches(c, version))
+    })
+}
+
+fn comparator_matches(comparator: &str, version: &Version) -> bool {
+    let comparator = comparator.trim();
+    let split = comparator.find(|c: char| !"<>=~^".contains(c)).unwrap_or(comparator.len());
+    let (op, ver) = comparator.split_at(split);
+    let ver = ver.strip_prefix('v').unwrap_or(ver);
+    if matches!(ver, "" | "*" | "x" | "X") {
+        return true;
+    }
+    // node-semver treats a bare version as an exact match, Cargo as a caret requirement
+    let op = if op.is_empty() { "=" } else { op };
+    Comparator::parse(&format!("{}{}", op, ver)).is_ok_and(|c| c.matches(version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn advisory(affected: Affected) -> Advisory {
+        Advisory {
+            id: "TEST-0001".into(),
+            aliases: vec![],
+            title: String::new(),
+            severity: Severity::Warning,
+            url: None,
+            fixed: None,
+            affected,
+        }
+    }
+
+    fn unless(reqs: &[&str]) -> Advisory {
+        advisory(Affected::Unless(reqs.iter().map(|r| VersionReq::parse(r).unwrap()).collect()))
+    }
+
+    fn v(version: &str) -> Version {
+        Version::parse(version).unwrap()
+    }
+
+    #[test]
+    fn rustsec_releases_are_affected_unless_patched() {
+        let a = unless(&[">=1.5.0"]);
+        assert!(a.affects(&v("1.4.9")));
+        assert!(!a.affects(&v("1.5.0")));
+    }
+
+    #[test]
+    fn prereleases_the_ranges_do_not_mention_are_not_flagged() {
+        assert!(!unless(&[">=1.5.0"]).affects(&v("2.0.0-rc.1")));
+        assert!(!advisory(Affected::Range("<1.5.0".into())).affects(&v("1.2.0-rc.1")));
+    }
+
+    #[test]
+    fn prereleases_the_ranges_mention_are_judged() {
+        let a = unless(&[">=1.5.0-rc.2"]);
+        assert!(a.affects(&v("1.5.0-rc.1")));
+        assert!(!a.affects(&v("1.5.0-rc.2")));
+        assert!(advisory(Affected::Range("<=1.5.0-beta.3".into())).affects(&v("1.5.0-beta.1")));
+    }
+
+    #[test]
+    fn npm_ranges_support_sets_and_hyphens() {
+        assert!(npm_range_matches(">= 1.0.0 <1.2.0 || 2.0.0", &v("1.1.0")));
+        assert!(npm_range_matches(">= 1.0.0 <1.2.0 || 2.0.0", &v("2.0.0")));
+        assert!(!npm_range_matches(">= 1.0.0 <1.2.0 || 2.0.0", &v("1.2.0")));
+        assert!(npm_range_matches("1.0.0 - 1.3.0", &v("1.3.0")));
+        assert!(npm_range_matches("*", &v("9.9.9")));
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crate::sentry::report::post_json;
+use crate::sentry::{AuditFinding, SentryReport, Severity};
+
+// --- Alerting ---
+//
+// Turns findings at or above `min_severity` into notifications. Each alert is
+// keyed by `rule_id:title`; it fires once, repeats only after the cooldown
+// (or when it escalates), and sends a recovery notice once its rule runs
+// again without reproducing it. Active alerts survive daemon restarts.
+// A rule that only inspects changed files must therefore carry its findings
+// forward for unchanged ones (as the secrets rule does through the Sentry
+// state), or the next quiet run would report a false recovery.
+
+pub const DEFAULT_LOG_URL: &str = "http://localhost:8000/api/messages";
+const SENDER: &str = "Sentry";
+
+fn default_min_severity() -> Severity {
+    Severity::Critical
+}
+
+fn default_cooldown() -> u64 {
+    3600
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_log_url() -> Option<String> {
+    Some(DEFAULT_LOG_URL.to_string())
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AlertConfig {
+    #[serde(default = "default_min_severity")]
+    pub min_severity: Severity,
+    #[serde(default = "default_cooldown")]
+    pub cooldown_secs: u64,
+    #[serde(default = "default_log_url")]
+    pub log_url: Option<String>, // Conversation log endpoint; `null` disables
+    #[serde(default = "default_true")]
+    pub desktop: bool, // notify-send
+    #[serde(default)]
+    pub webhooks: Vec<String>,
+    #[serde(default = "default_true")]
+    pub recovery: bool,
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        Self {
+            min_severity: default_min_severity(),
+            cooldown_secs: default_cooldown(),
+            log_url: default_log_url(),
+            desktop: true,
+            webhooks: vec![],
+            recovery: true,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActiveAlert {
+    pub rule_id: String,
+    pub title: String,
+    pub severity: Severity,
+    pub message: String,
+    pub first_seen: i64, // Unix seconds
+    pub last_notified: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertEvent {
+    Alert,
+    Reminder,
+    Escalation,
+    Recovery,
+}
+
+#[derive(Debug, Serialize)]
+struct WebhookPayload<'a> {
+    event: AlertEvent,
+    key: &'a str,
+    rule_id: &'a str,
+    title: &'a str,
+    message: &'a str,
+    severity: Severity,
+    timestamp: &'a str,
+    workspace: &'a str,
+}
+
+pub struct Alerter {
+    pub state_file: PathBuf,
+    pub active: BTreeMap<String, ActiveAlert>,
+}
+
+fn alert_key(finding: &AuditFinding) -> String {
+    format!("{}:{}", finding.rule_id, finding.title)
+}
+
+impl Alerter {
+    pub fn load(state_file: &Path) -> Self {
+        let active = fs::read_to_string(state_file).ok()
+            .and_then(|c| serde_json::from_str(&c).ok())
+            .unwrap_or_default();
+        Self { state_file: state_file.to_path_buf(), active }
+    }
+
+    fn save(&self) {
+        if let Ok(json) = serde_json::to_string_pretty(&self.active) {
+            let _ = fs::write(&self.state_file, json);
+        }
+    }
+
+    /// Sends notifications for the report and updates the active alert set.
+    pub fn process(&mut self, config: &AlertConfig, report: &SentryReport) {
+        let now = Local::now().timestamp();
+        let mut seen = vec![];
+
+        for finding in report.findings.iter().filter(|f| f.severity >= config.min_severity) {
+            let key = alert_key(finding);
+            seen.push(key.clone());
+
+            let event = match self.active.get(&key) {
+                None => Some(AlertEvent::Alert),
+                Some(a) if finding.severity > a.severity => Some(AlertEvent::Escalation),
+                Some(a) if now - a.last_notified >= config.cooldown_secs as i64 => Some(AlertEvent::Reminder),
+                Some(_) => None,
+            };
+
+            let entry = self.active.entry(key.clone()).or_insert_with(|| ActiveAlert {
+                rule_id: finding.rule_id.clone(),
+                title: finding.title.clone(),
+                severity: finding.severity,
+                message: finding.message.clone(),
+                first_seen: now,
+                last_notified: now,
+            });
+            entry.severity = finding.severity;
+            entry.message = finding.message.clone();
+
+            if let Some(event) = event {
+                entry.last_notified = now;
+                let alert = entry.clone();
+                notify(config, report, event, &key, &alert);
+            }
+        }
+
+        // Only rules that ran this time can confirm a condition has cleared
+        let recovered: Vec<String> = self.active.iter()
+            .filter(|(key, a)| !seen.contains(key) && report.rules.contains(&a.rule_id))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in recovered {
+            if let Some(alert) = self.active.remove(&key)
+                && config.recovery
+            {
+                notify(config, report, AlertEvent::Recovery, &key, &alert);
+            }
+        }
+
+        self.save();
+    }
+}
+
+fn format_alert(event: AlertEvent, alert: &ActiveAlert) -> String {
+    match event {
+        AlertEvent::Alert => format!("{} {}: {}", alert.severity.label(), alert.title, alert.message),
+        AlertEvent::Escalation => format!("{} Escalated - {}: {}", alert.severity.label(), alert.title, alert.message),
+        AlertEvent::Reminder => {
+            let since = chrono::DateTime::from_timestamp(alert.first_seen, 0)
+                .map(|d| d.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_default();
+            format!("{} Still active since {} - {}: {}", alert.severity.label(), since, alert.title, alert.message)
+        }
+        AlertEvent::Recovery => format!("✅ [RECOVERED] {} (was {})", alert.title, alert.severity.as_str()),
+    }
+}
+
+fn notify(config: &AlertConfig, report: &SentryReport, event: AlertEvent, key: &str, alert: &ActiveAlert) {
+    let text = format_alert(event, alert);
+
+    if let Some(url) = &config.log_url {
+        let message = serde_json::json!({ "sender": SENDER, "message": text });
+        if let Err(e) = post_json(url, &message) {
+            eprintln!("⚠️ Could not post alert to {}: {}", url, e);
+        }
+    }
+
+    if config.desktop {
+        let urgency = if event == AlertEvent::Recovery { "normal" } else { "critical" };
+        let _ = Command::new("notify-send")
+            .args(["-u", urgency, "-a", SENDER, &format!("Sentry: {}", alert.title), &text])
+            .output();
+    }
+
+    let payload = WebhookPayload {
+        event,
+        key,
+        rule_id: &alert.rule_id,
+        title: &alert.title,
+        message: &alert.message,
+        severity: alert.severity,
+        timestamp: &report.timestamp,
+        workspace: &report.workspace,
+    };
+    for url in &config.webhooks {
+        if let Err(e) = post_json(url, &payload) {
+            eprintln!("⚠️ Webhook {} failed: {}", url, e);
+        }
+    }
+}
@@ -0,0 +1,304 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::{MetadataExt, OpenOptionsExt};
+use std::path::Path;
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crate::sentry::snapshot::hash_file;
+
+// --- Integrity Baseline ---
+//
+// A tripwire for a fixed list of critical files. `sentry baseline create`
+// records mode, owner, size and BLAKE3 hash of every designated path (and
+// whether it exists at all); each run compares against that record. State
+// the server rewrites at runtime is listed as metadata-only: its content is
+// expected to change, so only existence, mode and owner are compared. The
+// baseline is signed with a keyed BLAKE3 MAC so edits to the baseline file
+// itself are detected as long as the key stays private.
+
+pub const DEFAULT_BASELINE_FILE: &str = "/home/a2/Desktop/gem/opb/backend/.sentry_baseline.json";
+pub const DEFAULT_KEY_FILE: &str = "/home/a2/.config/mission-control/sentry_baseline.key";
+const BASELINE_VERSION: u32 = 1;
+
+fn default_file() -> String {
+    DEFAULT_BASELINE_FILE.to_string()
+}
+
+fn default_key_file() -> String {
+    DEFAULT_KEY_FILE.to_string()
+}
+
+/// Critical files; those in `default_metadata_only` are also included here.
+fn default_paths() -> Vec<String> {
+    [
+        "/home/a2/Desktop/gem/opb/backend/mission_state.json",
+        "/home/a2/Desktop/gem/opb/backend/docs.json",
+        "/home/a2/Desktop/gem/opb/backend/sentry_config.json",
+        "/home/a2/.bashrc",
+        "/home/a2/.bash_profile",
+        "/home/a2/.profile",
+        "/home/a2/.zshrc",
+        "/home/a2/.ssh/authorized_keys",
+    ].iter().map(|p| p.to_string()).collect()
+}
+
+/// State the server rewrites on every change; hashing it would be constant drift.
+fn default_metadata_only() -> Vec<String> {
+    [
+        "/home/a2/Desktop/gem/opb/backend/mission_state.json",
+        "/home/a2/Desktop/gem/opb/backend/docs.json",
+    ].iter().map(|p| p.to_string()).collect()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BaselineConfig {
+    #[serde(default = "default_file")]
+    pub file: String,
+    #[serde(default = "default_key_file")]
+    pub key_file: String, // Created with mode 0600 on first `baseline create`
+    #[serde(default = "default_paths")]
+    pub paths: Vec<String>,
+    #[serde(default = "default_metadata_only")]
+    pub metadata_only: Vec<String>, // Subset of `paths` recorded without size and hash
+}
+
+impl Default for BaselineConfig {
+    fn default() -> Self {
+        Self { file: default_file(), key_file: default_key_file(), paths: default_paths(), metadata_only: default_metadata_only() }
+    }
+}
+
+impl BaselineConfig {
+    /// The current state of `path`, without content for metadata-only paths.
+    pub fn capture(&self, path: &str) -> BaselineEntry {
+        let entry = BaselineEntry::capture(Path::new(path));
+        if self.metadata_only.iter().any(|p| p == path) {
+            BaselineEntry { size: 0, hash: None, ..entry }
+        } else {
+            entry
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct BaselineEntry {
+    pub exists: bool,
+    #[serde(default)]
+    pub mode: u32, // Permission bits only
+    #[serde(default)]
+    pub uid: u32,
+    #[serde(default)]
+    pub gid: u32,
+    #[serde(default)]
+    pub size: u64,
+    #[serde(default)]
+    pub hash: Option<String>,
+}
+
+impl BaselineEntry {
+    pub fn capture(path: &Path) -> Self {
+        match fs::metadata(path) {
+            Ok(meta) => Self {
+                exists: true,
+                mode: meta.mode() & 0o7777,
+                uid: meta.uid(),
+                gid: meta.gid(),
+                size: meta.len(),
+                hash: if meta.is_file() { hash_file(path) } else { None },
+            },
+            Err(_) => Self { exists: false, mode: 0, uid: 0, gid: 0, size: 0, hash: None },
+        }
+    }
+
+    /// Human-readable differences from `self` (the baseline) to `current`.
+    pub fn drift(&self, current: &BaselineEntry) -> Vec<String> {
+        match (self.exists, current.exists) {
+            (true, false) => return vec!["deleted".into()],
+            (false, true) => return vec!["created".into()],
+            (false, false) => return vec![],
+            (true, true) => {}
+        }
+        let mut changes = vec![];
+        if self.hash != current.hash {
+            changes.push("content changed".to_string());
+        }
+        if self.size != current.size {
+            changes.push(format!("size {} -> {}", self.size, current.size));
+        }
+        if self.mode != current.mode {
+            changes.push(format!("mode {:o} -> {:o}", self.mode, current.mode));
+        }
+        if self.uid != current.uid || self.gid != current.gid {
+            changes.push(format!("owner {}:{} -> {}:{}", self.uid, self.gid, current.uid, current.gid));
+        }
+        changes
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Baseline {
+    pub version: u32,
+    pub updated: String,
+    pub entries: BTreeMap<String, BaselineEntry>,
+    pub signature: String, // keyed BLAKE3 of `entries`
+}
+
+fn load_key(key_file: &str, create: bool) -> Result<[u8; 32], String> {
+    if let Ok(hex) = fs::read_to_string(key_file) {
+        let bytes = (0..64).step_by(2)
+            .map(|i| hex.get(i..i + 2).and_then(|b| u8::from_str_radix(b, 16).ok()))
+            .collect::<Option<Vec<u8>>>()
+            .ok_or_else(|| format!("invalid key in {}", key_file))?;
+        return bytes.try_into().map_err(|_| format!("invalid key in {}", key_file));
+    }
+    if !create {
+        return Err(format!("key file {} is missing", key_file));
+    }
+
+    let key: [u8; 32] = rand::random();
+    if let Some(dir) = Path::new(key_file).parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let mut file = fs::OpenOptions::new().write(true).create_new(true).mode(0o600).open(key_file)
+        .map_err(|e| format!("could not create {}: {}", key_file, e))?;
+    let hex: String = key.iter().map(|b| format!("{:02x}", b)).collect();
+    file.write_all(hex.as_bytes()).map_err(|e| e.to_string())?;
+    Ok(key)
+}
+
+fn sign(key: &[u8; 32], entries: &BTreeMap<String, BaselineEntry>) -> String {
+    let canonical = serde_json::to_vec(entries).unwrap_or_default();
+    blake3::keyed_hash(key, &canonical).to_hex().to_string()
+}
+
+impl Baseline {
+    pub fn load(config: &BaselineConfig) -> Option<Self> {
+        fs::read_to_string(&config.file).ok()
+            .and_then(|c| serde_json::from_str(&c).ok())
+    }
+
+    /// Whether the signature matches the entries under the configured key.
+    pub fn verify(&self, config: &BaselineConfig) -> Result<(), String> {
+        let key = load_key(&config.key_file, false)?;
+        if sign(&key, &self.entries) != self.signature {
+            return Err("signature does not match".into());
+        }
+        Ok(())
+    }
+
+    fn save(&mut self, config: &BaselineConfig) -> Result<(), String> {
+        let key = load_key(&config.key_file, true)?;
+        self.version = BASELINE_VERSION;
+        self.updated = Local::now().to_rfc3339();
+        self.signature = sign(&key, &self.entries);
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(&config.file, json).map_err(|e| format!("could not write {}: {}", config.file, e))
+    }
+
+    /// Records every configured path from scratch. Refuses to replace an existing baseline.
+    pub fn create(config: &BaselineConfig) -> Result<Self, String> {
+        if Path::new(&config.file).exists() {
+            return Err(format!("{} already exists; use `sentry baseline accept` to update it", config.file));
+        }
+        let mut baseline = Self {
+            entries: config.paths.iter()
+                .map(|p| (p.clone(), config.capture(p)))
+                .collect(),
+            ..Self::default()
+        };
+        baseline.save(config)?;
+        Ok(baseline)
+    }
+
+    /// Re-records `paths` (every configured path when empty) after an intended change.
+    /// Paths no longer in the config are dropped on a full accept.
+    pub fn accept(config: &BaselineConfig, paths: &[String]) -> Result<Vec<String>, String> {
+        let mut baseline = Self::load(config)
+            .ok_or_else(|| format!("no baseline at {}; run `sentry baseline create` first", config.file))?;
+        if let Some(unknown) = paths.iter().find(|p| !config.paths.contains(p) && !baseline.entries.contains_key(*p)) {
+            return Err(format!("{} is not a baselined path", unknown));
+        }
+        let targets: Vec<String> = if paths.is_empty() {
+            baseline.entries.retain(|p, _| config.paths.contains(p));
+            config.paths.clone()
+        } else {
+            paths.to_vec()
+        };
+        for path in &targets {
+            baseline.entries.insert(path.clone(), config.capture(path));
+        }
+        baseline.save(config)?;
+        Ok(targets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn config(dir: &Path, paths: &[&Path]) -> BaselineConfig {
+        BaselineConfig {
+            file: dir.join("baseline.json").to_string_lossy().into_owned(),
+            key_file: dir.join("keys/baseline.key").to_string_lossy().into_owned(),
+            paths: paths.iter().map(|p| p.to_string_lossy().into_owned()).collect(),
+            metadata_only: vec![],
+        }
+    }
+
+    #[test]
+    fn created_baseline_verifies_and_key_is_private() {
+        let dir = tempfile::tempdir().unwrap();
+        let watched = dir.path().join("watched");
+        fs::write(&watched, "a").unwrap();
+        let config = config(dir.path(), &[&watched]);
+
+        Baseline::create(&config).unwrap();
+        let loaded = Baseline::load(&config).unwrap();
+        assert!(loaded.verify(&config).is_ok());
+        assert_eq!(fs::metadata(&config.key_file).unwrap().mode() & 0o777, 0o600);
+    }
+
+    #[test]
+    fn tampered_entries_fail_verification() {
+        let dir = tempfile::tempdir().unwrap();
+        let watched = dir.path().join("watched");
+        fs::write(&watched, "a").unwrap();
+        let config = config(dir.path(), &[&watched]);
+
+        let mut baseline = Baseline::create(&config).unwrap();
+        baseline.entries.values_mut().for_each(|e| e.hash = None);
+        assert!(baseline.verify(&config).is_err());
+    }
+
+    #[test]
+    fn another_key_fails_verification() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config(dir.path(), &[]);
+        let baseline = Baseline::create(&config).unwrap();
+
+        fs::remove_file(&config.key_file).unwrap();
+        load_key(&config.key_file, true).unwrap();
+        assert!(baseline.verify(&config).is_err());
+    }
+
+    #[test]
+    fn metadata_only_paths_ignore_content_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = dir.path().join("state.json");
+        fs::write(&state, "{}").unwrap();
+        let mut config = config(dir.path(), &[&state]);
+        config.metadata_only = config.paths.clone();
+
+        let before = config.capture(&config.paths[0]);
+        assert_eq!(before.hash, None);
+        fs::write(&state, "{\"changed\": true}").unwrap();
+        assert!(before.drift(&config.capture(&config.paths[0])).is_empty());
+
+        fs::set_permissions(&state, fs::Permissions::from_mode(0o666)).unwrap();
+        assert!(!before.drift(&config.capture(&config.paths[0])).is_empty());
+    }
+}
@@ -0,0 +1,82 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+
+use crate::sentry::advisories::AdvisoryConfig;
+use crate::sentry::alerts::AlertConfig;
+use crate::sentry::baseline::BaselineConfig;
+use crate::sentry::ignore::IgnoreConfig;
+use crate::sentry::plugins::PluginConfig;
+use crate::sentry::report::DEFAULT_REPORT_URL;
+use crate::sentry::rules::ProcessConfig;
+use crate::sentry::Severity;
+
+// --- Sentry Configuration ---
+//
+// Loaded from a JSON file; every section is optional and falls back to the
+// built-in defaults so a missing or partial config behaves like before.
+
+/// Per-rule overrides, keyed by rule id in `SentryConfig::rules`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct RuleConfig {
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    #[serde(default)]
+    pub thresholds: BTreeMap<String, f64>,
+    #[serde(default)]
+    pub severity: BTreeMap<String, Severity>, // finding title or severity level -> replacement
+    #[serde(default)]
+    pub interval: Option<u64>, // Minimum seconds between runs
+}
+
+fn default_report_url() -> Option<String> {
+    Some(DEFAULT_REPORT_URL.to_string())
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SentryConfig {
+    #[serde(default)]
+    pub ignore: IgnoreConfig,
+    #[serde(default = "default_report_url")]
+    pub report_url: Option<String>, // `null` keeps reports local
+    #[serde(default)]
+    pub processes: ProcessConfig,
+    #[serde(default)]
+    pub rules: BTreeMap<String, RuleConfig>,
+    #[serde(default)]
+    pub alerts: AlertConfig,
+    #[serde(default)]
+    pub plugins: PluginConfig,
+    #[serde(default)]
+    pub advisories: AdvisoryConfig,
+    #[serde(default)]
+    pub baseline: BaselineConfig,
+}
+
+impl Default for SentryConfig {
+    fn default() -> Self {
+        Self {
+            ignore: IgnoreConfig::default(),
+            report_url: default_report_url(),
+            processes: ProcessConfig::default(),
+            rules: BTreeMap::new(),
+            alerts: AlertConfig::default(),
+            plugins: PluginConfig::default(),
+            advisories: AdvisoryConfig::default(),
+            baseline: BaselineConfig::default(),
+        }
+    }
+}
+
+impl SentryConfig {
+    pub fn load(path: &Path) -> Self {
+        match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                eprintln!("⚠️ Invalid Sentry config {}: {}. Using defaults.", path.display(), e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+use serde::{Deserialize, Serialize};
+
+// --- Ignore Rules ---
+//
+// Gitignore semantics for Sentry scans. Layers, from lowest to highest priority:
+//   1. built-in defaults (`.git/` and the usual dependency/build dirs)
+//   2. git's global excludes file (core.excludesFile)
+//   3. `.gitignore` then `.sentryignore` in each directory, root to leaf
+//   4. `ignore.patterns` from the Sentry config
+// The highest-priority layer with a matching (or `!`-negated) pattern wins.
+// Lockfiles are always scanned, since the deps rule audits them and projects
+// often gitignore them: only `.sentryignore` files and the config can exclude
+// them. They are still skipped inside ignored directories.
+
+pub const SENTRY_IGNORE_FILE: &str = ".sentryignore";
+const DEFAULT_PATTERNS: &[&str] = &[".git/", "__pycache__/", "node_modules/", ".venv/", "browser_data/", "target/"];
+const ALWAYS_INCLUDED: &[&str] = &["Cargo.lock", "package-lock.json"];
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IgnoreConfig {
+    #[serde(default = "default_true")]
+    pub gitignore: bool, // honour nested .gitignore files
+    #[serde(default = "default_true")]
+    pub global_excludes: bool,
+    #[serde(default)]
+    pub defaults: Option<Vec<String>>, // replaces DEFAULT_PATTERNS when set
+    #[serde(default)]
+    pub patterns: Vec<String>, // highest priority, gitignore syntax (supports `!`)
+}
+
+impl Default for IgnoreConfig {
+    fn default() -> Self {
+        Self { gitignore: true, global_excludes: true, defaults: None, patterns: vec![] }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IgnoreVerdict {
+    pub ignored: bool,
+    pub source: String,
+    pub pattern: String,
+}
+
+pub struct IgnoreRules {
+    workspace: PathBuf,
+    config: IgnoreConfig,
+    defaults: Arc<Gitignore>,
+    global: Arc<Gitignore>,
+    overrides: Arc<Gitignore>,
+    dirs: Mutex<HashMap<PathBuf, Vec<Layer>>>, // per-directory files, lazily loaded
+}
+
+/// A set of patterns, and whether it is Sentry's own (able to exclude `ALWAYS_INCLUDED` files).
+type Layer = (Arc<Gitignore>, bool);
+
+fn from_lines(root: &Path, source: &str, lines: &[String]) -> Arc<Gitignore> {
+    let mut builder = GitignoreBuilder::new(root);
+    for line in lines {
+        let _ = builder.add_line(Some(PathBuf::from(source)), line);
+    }
+    Arc::new(builder.build().unwrap_or_else(|_| Gitignore::empty()))
+}
+
+impl IgnoreRules {
+    pub fn new(workspace: &Path, config: IgnoreConfig) -> Self {
+        let defaults: Vec<String> = config.defaults.clone()
+            .unwrap_or_else(|| DEFAULT_PATTERNS.iter().map(|p| p.to_string()).collect());
+
+        let global = if config.global_excludes {
+            let mut builder = GitignoreBuilder::new(workspace);
+            if let Some(path) = ignore::gitignore::gitconfig_excludes_path() {
+                builder.add(path);
+            }
+            builder.build().unwrap_or_else(|_| Gitignore::empty())
+        } else {
+            Gitignore::empty()
+        };
+        let global = Arc::new(global);
+
+        Self {
+            workspace: workspace.to_path_buf(),
+            defaults: from_lines(workspace, "<defaults>", &defaults),
+            global,
+            overrides: from_lines(workspace, "<config>", &config.patterns),
+            config,
+            dirs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Drops cached per-directory matchers, e.g. after a `.gitignore` was edited.
+    pub fn invalidate(&self) {
+        self.dirs.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    fn dir_layers(&self, dir: &Path) -> Vec<Layer> {
+        let mut cache = self.dirs.lock().unwrap_or_else(|e| e.into_inner());
+        cache.entry(dir.to_path_buf()).or_insert_with(|| {
+            let mut files = vec![];
+            if self.config.gitignore {
+                files.push((dir.join(".gitignore"), false));
+            }
+            files.push((dir.join(SENTRY_IGNORE_FILE), true));
+
+            files.into_iter()
+                .filter(|(f, _)| f.is_file())
+                .filter_map(|(f, own)| {
+                    let mut builder = GitignoreBuilder::new(dir);
+                    builder.add(&f);
+                    builder.build().ok().map(|g| (Arc::new(g), own))
+                })
+                .collect()
+        }).clone()
+    }
+
+    /// Finds the decisive rule for `path`, or `None` when no rule applies.
+    pub fn explain(&self, path: &Path, is_dir: bool) -> Option<IgnoreVerdict> {
+        let Ok(rel) = path.strip_prefix(&self.workspace) else {
+            return None;
+        };
+
+        let always_included = !is_dir
+            && path.file_name().and_then(|n| n.to_str()).is_some_and(|n| ALWAYS_INCLUDED.contains(&n))
+            && !path.parent().is_some_and(|p| p != self.workspace && self.is_ignored(p, true));
+
+        // Highest priority first
+        let mut layers: Vec<Layer> = vec![(self.overrides.clone(), true)];
+        let mut dir = path.parent();
+        while let Some(d) = dir {
+            if !d.starts_with(&self.workspace) {
+                break;
+            }
+            layers.extend(self.dir_layers(d).into_iter().rev());
+            dir = d.parent();
+        }
+        layers.push((self.global.clone(), false));
+        layers.push((self.defaults.clone(), false));
+
+        for (layer, own) in &layers {
+            let target = path.strip_prefix(layer.path()).unwrap_or(rel);
+            if layer.is_empty() || target.as_os_str().is_empty() {
+                continue;
+            }
+            let verdict = match layer.matched_path_or_any_parents(target, is_dir) {
+                Match::None => continue,
+                Match::Ignore(glob) if always_included && !own => {
+                    return Some(IgnoreVerdict {
+                        ignored: false,
+                        source: "<always included>".into(),
+                        pattern: glob.original().to_string(),
+                    });
+                }
+                Match::Ignore(glob) => (true, glob),
+                Match::Whitelist(glob) => (false, glob),
+            };
+            return Some(IgnoreVerdict {
+                ignored: verdict.0,
+                source: verdict.1.from().map(|p| p.display().to_string()).unwrap_or_else(|| "<unknown>".into()),
+                pattern: verdict.1.original().to_string(),
+            });
+        }
+        None
+    }
+
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        self.explain(path, is_dir).is_some_and(|v| v.ignored)
+    }
+}
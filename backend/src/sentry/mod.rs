@@ -0,0 +1,366 @@
+pub mod snapshot;
+pub mod rules;
+pub mod watch;
+pub mod ignore;
+pub mod config;
+pub mod report;
+pub mod alerts;
+pub mod plugins;
+pub mod advisories;
+pub mod baseline;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use walkdir::WalkDir;
+use chrono::Local;
+
+use snapshot::{hash_file, record_sample, ChangeSet, FileMetadata, GrowthSample, SecretIndex, SentryState, Snapshot, STATE_VERSION};
+use config::{RuleConfig, SentryConfig};
+use ignore::{IgnoreRules, IgnoreVerdict, SENTRY_IGNORE_FILE};
+use report::{post_report, OutputFormat};
+use alerts::Alerter;
+
+pub use report::{AuditFinding, AuditRecommendation, SentryReport, Severity};
+
+const WORKSPACE: &str = "/home/a2/Desktop/gem";
+const STATE_FILE: &str = "/home/a2/Desktop/gem/opb/backend/.sentry_state.json";
+const ALERT_STATE_FILE: &str = "/home/a2/Desktop/gem/opb/backend/.sentry_alerts.json";
+pub const CONFIG_FILE: &str = "/home/a2/Desktop/gem/opb/backend/sentry_config.json";
+/// Written by the server whenever a report is published; scanning them would
+/// turn every publish into a change that triggers the next one in watch mode.
+const SERVER_OUTPUT_FILES: &[&str] = &[
+    "/home/a2/Desktop/gem/opb/backend/sentry_reports.json",
+    "/home/a2/Desktop/gem/opb/backend/sentry_reports.json.lock",
+];
+
+pub trait AuditRule {
+    /// Stable identifier stamped on every finding the rule produces.
+    fn id(&self) -> &str;
+    fn description(&self) -> &str;
+    /// Tunable thresholds with their defaults; read them through `SentryAudit::threshold`.
+    fn thresholds(&self) -> &'static [(&'static str, f64)] {
+        &[]
+    }
+    /// Expensive rules only run on full scans unless the config gives them an interval.
+    fn full_scan_only(&self) -> bool {
+        false
+    }
+    /// Minimum seconds between runs when the config sets no interval, for rules
+    /// too costly to repeat on every watch-mode change but needed between full scans.
+    fn default_interval(&self) -> Option<u64> {
+        None
+    }
+    fn run(&self, ctx: &SentryAudit) -> (Vec<AuditFinding>, Vec<AuditRecommendation>);
+}
+
+pub struct SentryAudit {
+    pub workspace: PathBuf,
+    pub state_file: PathBuf,
+    pub server_outputs: Vec<PathBuf>, // Never scanned, see SERVER_OUTPUT_FILES
+    pub current_snapshot: Snapshot,
+    pub previous_snapshot: Snapshot,
+    pub history: Vec<GrowthSample>, // Sampled workspace sizes, oldest first
+    pub secrets: Mutex<SecretIndex>, // Kept up to date by the secrets rule
+    pub hash_contents: bool,
+    pub config: SentryConfig,
+    pub ignore: IgnoreRules,
+    pub notices: Vec<AuditFinding>, // Raised outside the rules (e.g. by the watcher); drained into the next report
+    pub format: OutputFormat,
+    pub rule_filter: Option<Vec<String>>, // Set by `--rules`; runs exactly these rules
+    pub alerter: Alerter,
+    last_run: HashMap<String, Instant>,
+}
+
+impl Default for SentryAudit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SentryAudit {
+    pub fn new() -> Self {
+        Self::with_config(SentryConfig::load(Path::new(CONFIG_FILE)))
+    }
+
+    pub fn with_config(config: SentryConfig) -> Self {
+        let workspace = PathBuf::from(WORKSPACE);
+        Self {
+            ignore: IgnoreRules::new(&workspace, config.ignore.clone()),
+            workspace,
+            state_file: PathBuf::from(STATE_FILE),
+            server_outputs: SERVER_OUTPUT_FILES.iter().map(PathBuf::from).collect(),
+            current_snapshot: Snapshot::new(),
+            previous_snapshot: Snapshot::new(),
+            history: vec![],
+            secrets: Mutex::new(SecretIndex::new()),
+            hash_contents: false,
+            config,
+            notices: vec![],
+            format: OutputFormat::Text,
+            rule_filter: None,
+            alerter: Alerter::load(Path::new(ALERT_STATE_FILE)),
+            last_run: HashMap::new(),
+        }
+    }
+
+    pub fn load_previous_state(&mut self) {
+        if let Ok(content) = fs::read_to_string(&self.state_file)
+            && let Ok(state) = serde_json::from_str::<SentryState>(&content)
+            && state.version == STATE_VERSION
+        {
+            self.previous_snapshot = state.files;
+            self.history = state.history;
+            self.secrets = Mutex::new(state.secrets);
+        }
+    }
+
+    pub fn save_current_state(&self) {
+        let state = SentryState {
+            version: STATE_VERSION,
+            timestamp: Local::now().to_rfc3339(),
+            files: self.current_snapshot.clone(),
+            history: self.history.clone(),
+            secrets: self.secrets.lock().unwrap_or_else(|e| e.into_inner()).clone(),
+        };
+        if let Ok(json) = serde_json::to_string_pretty(&state) {
+            let _ = fs::write(&self.state_file, json);
+        }
+    }
+
+    pub fn is_ignored_entry(&self, path: &Path, is_dir: bool) -> bool {
+        path == self.state_file
+            || path == self.alerter.state_file
+            || self.server_outputs.iter().any(|p| p == path)
+            || self.ignore.is_ignored(path, is_dir)
+    }
+
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        self.is_ignored_entry(path, path.is_dir())
+    }
+
+    /// Describes which ignore rule (if any) decides whether `path` is scanned.
+    pub fn explain_ignore(&self, path: &Path) -> String {
+        let abs = if path.is_absolute() { path.to_path_buf() } else { self.workspace.join(path) };
+        if abs == self.state_file {
+            return format!("{}: ignored (Sentry state file)", abs.display());
+        }
+        if self.server_outputs.contains(&abs) {
+            return format!("{}: ignored (written by the server on every report)", abs.display());
+        }
+        match self.ignore.explain(&abs, abs.is_dir()) {
+            Some(IgnoreVerdict { ignored: true, source, pattern }) => format!("{}: ignored by {} (`{}`)", abs.display(), source, pattern),
+            Some(IgnoreVerdict { ignored: false, source, pattern }) => format!("{}: re-included by {} (`{}`)", abs.display(), source, pattern),
+            None => format!("{}: not ignored (no matching rule)", abs.display()),
+        }
+    }
+
+    /// Whether `path` is an ignore file whose edits change the rules.
+    pub fn is_ignore_file(path: &Path) -> bool {
+        path.file_name().is_some_and(|n| n == ".gitignore" || n == SENTRY_IGNORE_FILE)
+    }
+
+    pub fn relative_path(&self, path: &Path) -> String {
+        path.strip_prefix(&self.workspace).unwrap_or(path).to_string_lossy().to_string()
+    }
+
+    /// Builds the snapshot entry for a single file, reusing the previous hash
+    /// when size and mtime are unchanged.
+    pub fn stat_file(&self, path: &Path, rel_path: &str) -> Option<FileMetadata> {
+        let metadata = fs::metadata(path).ok().filter(|m| m.is_file())?;
+        let mtime = metadata.modified().unwrap_or(SystemTime::now())
+            .duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+
+        let mut meta = FileMetadata {
+            size: metadata.len(),
+            mtime,
+            hash: None,
+        };
+
+        if self.hash_contents {
+            meta.hash = match self.previous_snapshot.get(rel_path) {
+                Some(prev) if prev.hash.is_some() && prev.size == meta.size && prev.mtime == meta.mtime => prev.hash.clone(),
+                _ => hash_file(path),
+            };
+        }
+        Some(meta)
+    }
+
+    /// Adds every non-ignored file below `root` to the current snapshot.
+    pub fn scan_tree(&mut self, root: &Path) {
+        let found: Vec<(String, FileMetadata)> = WalkDir::new(root).into_iter()
+            .filter_entry(|e| !self.is_ignored_entry(e.path(), e.file_type().is_dir()))
+            .flatten()
+            .filter(|e| e.file_type().is_file())
+            .filter_map(|entry| {
+                let rel_path = self.relative_path(entry.path());
+                self.stat_file(entry.path(), &rel_path).map(|meta| (rel_path, meta))
+            })
+            .collect();
+        self.current_snapshot.extend(found);
+    }
+
+    pub fn scan_filesystem(&mut self) {
+        self.current_snapshot.clear();
+        self.ignore.invalidate();
+        let workspace = self.workspace.clone();
+        self.scan_tree(&workspace);
+    }
+
+    pub fn changes(&self) -> ChangeSet {
+        ChangeSet::compute(&self.previous_snapshot, &self.current_snapshot)
+    }
+
+    /// Scans and prints every change since the last audit without updating the saved state.
+    pub fn diff_report(&mut self) -> String {
+        self.load_previous_state();
+        self.scan_filesystem();
+
+        let changes = self.changes();
+        let mut lines: Vec<String> = changes.entries().into_iter()
+            .map(|(kind, path)| format!("{} {}", kind.marker(), path))
+            .collect();
+        lines.push(format!("{} changes (+{} ~{} -{} >{}), {} touched.",
+            changes.churn(), changes.created.len(), changes.modified.len(),
+            changes.deleted.len(), changes.renamed.len(), changes.touched.len()));
+        lines.join("\n")
+    }
+
+    pub fn run(&mut self, full_scan: bool) -> SentryReport {
+        self.load_previous_state();
+        self.scan_filesystem();
+        self.evaluate(full_scan)
+    }
+
+    /// Built-in rules followed by the plugins currently in the rules directory.
+    pub fn rules(&self) -> Vec<Box<dyn AuditRule>> {
+        let mut all = rules::all();
+        for plugin in plugins::discover(Path::new(&self.config.plugins.dir)) {
+            all.push(Box::new(plugin));
+        }
+        all
+    }
+
+    pub fn rule_config(&self, id: &str) -> RuleConfig {
+        self.config.rules.get(id).cloned().unwrap_or_default()
+    }
+
+    /// Configured value for a rule threshold, falling back to the rule's default.
+    pub fn threshold(&self, rule: &dyn AuditRule, key: &str) -> f64 {
+        self.config.rules.get(rule.id())
+            .and_then(|c| c.thresholds.get(key).copied())
+            .or_else(|| rule.thresholds().iter().find(|(k, _)| *k == key).map(|(_, v)| *v))
+            .unwrap_or_default()
+    }
+
+    fn should_run(&self, rule: &dyn AuditRule, full_scan: bool) -> bool {
+        if let Some(filter) = &self.rule_filter {
+            return filter.iter().any(|id| id == rule.id());
+        }
+        let config = self.rule_config(rule.id());
+        if config.enabled == Some(false) {
+            return false;
+        }
+        match config.interval.or(rule.default_interval()) {
+            Some(secs) => self.last_run.get(rule.id()).is_none_or(|t| t.elapsed().as_secs() >= secs),
+            None => full_scan || !rule.full_scan_only(),
+        }
+    }
+
+    /// Applies the rule's severity overrides; a title match beats a level match.
+    fn override_severity(config: &RuleConfig, finding: &mut AuditFinding) {
+        if let Some(severity) = config.severity.get(&finding.title).or_else(|| config.severity.get(finding.severity.as_str())) {
+            finding.severity = *severity;
+        }
+    }
+
+    /// Describes every rule with its effective settings (`--list-rules`).
+    pub fn describe_rules(&self) -> String {
+        let mut lines = vec![];
+        for rule in self.rules() {
+            let config = self.rule_config(rule.id());
+            let enabled = config.enabled.unwrap_or(true);
+            let schedule = match config.interval {
+                Some(secs) => format!("every {}s", secs),
+                None if let Some(secs) = rule.default_interval() => format!("every {}s (default)", secs),
+                None if rule.full_scan_only() => "full scans only".to_string(),
+                None => "every run".to_string(),
+            };
+            lines.push(format!("{} [{}, {}] - {}", rule.id(), if enabled { "enabled" } else { "disabled" }, schedule, rule.description()));
+            for (key, default) in rule.thresholds() {
+                let value = self.threshold(rule.as_ref(), key);
+                if value == *default {
+                    lines.push(format!("    {} = {}", key, value));
+                } else {
+                    lines.push(format!("    {} = {} (default {})", key, value, default));
+                }
+            }
+            for (key, value) in &config.thresholds {
+                if !rule.thresholds().iter().any(|(k, _)| k == key) {
+                    lines.push(format!("    {} = {} (configured)", key, value));
+                }
+            }
+            for (key, severity) in &config.severity {
+                lines.push(format!("    severity {} -> {}", key, severity.as_str()));
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// Runs the rules against the current snapshot and persists it as the new baseline.
+    pub fn evaluate(&mut self, full_scan: bool) -> SentryReport {
+        let now = Local::now();
+        let timestamp = now.to_rfc3339();
+        let mut all_findings: Vec<AuditFinding> = self.notices.drain(..).collect();
+        let mut all_recommendations = vec![];
+        let mut ran = vec![];
+
+        for rule in self.rules() {
+            if !self.should_run(rule.as_ref(), full_scan) {
+                continue;
+            }
+            ran.push(rule.id().to_string());
+            let config = self.rule_config(rule.id());
+            let (f, r) = rule.run(self);
+            self.last_run.insert(rule.id().to_string(), Instant::now());
+            all_findings.extend(f.into_iter().map(|mut f| {
+                f.rule_id = rule.id().to_string();
+                Self::override_severity(&config, &mut f);
+                f
+            }));
+            all_recommendations.extend(r.into_iter().map(|mut r| {
+                r.rule_id = rule.id().to_string();
+                r
+            }));
+        }
+        all_findings.iter_mut()
+            .filter(|f| f.timestamp.is_empty())
+            .for_each(|f| f.timestamp = timestamp.clone());
+
+        record_sample(&mut self.history, &self.current_snapshot, now.timestamp().max(0) as u64);
+        self.save_current_state();
+        self.previous_snapshot = self.current_snapshot.clone();
+
+        SentryReport {
+            id: None,
+            timestamp,
+            workspace: self.workspace.display().to_string(),
+            findings: all_findings,
+            recommendations: all_recommendations,
+            rules: ran,
+        }
+    }
+
+    /// Prints the report in the selected format, forwards it to the server and raises alerts.
+    pub fn publish(&mut self, report: &SentryReport) {
+        println!("{}", report.render(self.format));
+        if let Some(url) = &self.config.report_url
+            && let Err(e) = post_report(url, report)
+        {
+            eprintln!("⚠️ Could not post Sentry report to {}: {}", url, e);
+        }
+        self.alerter.process(&self.config.alerts, report);
+    }
+}
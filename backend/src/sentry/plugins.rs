@@ -0,0 +1,301 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::sentry::snapshot::Snapshot;
+use crate::sentry::{AuditFinding, AuditRecommendation, AuditRule, SentryAudit, Severity};
+
+// --- Plugin Rules ---
+//
+// Every executable in the rules directory is run as an audit rule with id
+// `plugin:<file name>`. It receives a `PluginContext` as JSON on stdin and must
+// print a `PluginResponse` on stdout before the timeout. Unknown fields,
+// unknown severities and oversized responses are rejected.
+
+pub const DEFAULT_RULES_DIR: &str = "/home/a2/Desktop/gem/opb/backend/sentry_rules.d";
+const PROTOCOL_VERSION: u32 = 1;
+const MAX_FINDINGS: usize = 100;
+const MAX_TEXT_LEN: usize = 4096;
+const MAX_STDERR: u64 = 4096;
+
+fn default_dir() -> String {
+    DEFAULT_RULES_DIR.to_string()
+}
+
+fn default_timeout() -> u64 {
+    10
+}
+
+fn default_max_output_kb() -> u64 {
+    256
+}
+
+fn default_memory_mb() -> u64 {
+    512
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PluginConfig {
+    #[serde(default = "default_dir")]
+    pub dir: String,
+    #[serde(default = "default_timeout")]
+    pub timeout_secs: u64, // Wall clock; also the CPU-time limit
+    #[serde(default = "default_max_output_kb")]
+    pub max_output_kb: u64,
+    #[serde(default = "default_memory_mb")]
+    pub memory_mb: u64, // Address-space limit
+}
+
+impl Default for PluginConfig {
+    fn default() -> Self {
+        Self {
+            dir: default_dir(),
+            timeout_secs: default_timeout(),
+            max_output_kb: default_max_output_kb(),
+            memory_mb: default_memory_mb(),
+        }
+    }
+}
+
+/// Sent to the plugin on stdin.
+#[derive(Debug, Serialize)]
+pub struct PluginContext<'a> {
+    pub version: u32,
+    pub rule_id: &'a str,
+    pub workspace: String,
+    pub timestamp: String,
+    pub changes: PluginChanges,
+    pub previous: &'a Snapshot,
+    pub thresholds: BTreeMap<String, f64>, // From `rules.<id>.thresholds` in the Sentry config
+}
+
+#[derive(Debug, Serialize)]
+pub struct PluginChanges {
+    pub created: Vec<String>,
+    pub modified: Vec<String>,
+    pub deleted: Vec<String>,
+    pub renamed: Vec<(String, String)>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PluginFinding {
+    title: String,
+    message: String,
+    severity: Severity,
+    #[serde(default)]
+    paths: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PluginRecommendation {
+    action: String,
+}
+
+/// Expected on stdout.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PluginResponse {
+    #[serde(default)]
+    findings: Vec<PluginFinding>,
+    #[serde(default)]
+    recommendations: Vec<PluginRecommendation>,
+}
+
+impl PluginResponse {
+    fn validate(&self) -> Result<(), String> {
+        if self.findings.len() > MAX_FINDINGS {
+            return Err(format!("{} findings (max {})", self.findings.len(), MAX_FINDINGS));
+        }
+        for f in &self.findings {
+            if f.title.trim().is_empty() {
+                return Err("finding with an empty title".into());
+            }
+            if f.title.len() > MAX_TEXT_LEN || f.message.len() > MAX_TEXT_LEN {
+                return Err(format!("finding '{}' exceeds {} bytes", f.title.chars().take(40).collect::<String>(), MAX_TEXT_LEN));
+            }
+        }
+        if let Some(r) = self.recommendations.iter().find(|r| r.action.trim().is_empty() || r.action.len() > MAX_TEXT_LEN) {
+            return Err(format!("invalid recommendation '{}'", r.action.chars().take(40).collect::<String>()));
+        }
+        Ok(())
+    }
+}
+
+pub struct PluginRule {
+    id: String,
+    description: String,
+    path: PathBuf,
+}
+
+/// Executables in `dir`, sorted by name. Hidden files and backups are skipped.
+pub fn discover(dir: &Path) -> Vec<PluginRule> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut plugins: Vec<PluginRule> = entries.flatten()
+        .filter(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            !name.starts_with('.') && !name.ends_with('~')
+        })
+        .filter(|e| e.metadata().is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0))
+        .map(|e| PluginRule {
+            id: format!("plugin:{}", e.file_name().to_string_lossy()),
+            description: format!("External rule {}", e.path().display()),
+            path: e.path(),
+        })
+        .collect();
+    plugins.sort_by(|a, b| a.id.cmp(&b.id));
+    plugins
+}
+
+impl PluginRule {
+    fn execute(&self, ctx: &SentryAudit) -> Result<PluginResponse, String> {
+        let config = &ctx.config.plugins;
+        let changes = ctx.changes();
+        let context = PluginContext {
+            version: PROTOCOL_VERSION,
+            rule_id: &self.id,
+            workspace: ctx.workspace.display().to_string(),
+            timestamp: chrono::Local::now().to_rfc3339(),
+            changes: PluginChanges {
+                created: changes.created,
+                modified: changes.modified,
+                deleted: changes.deleted,
+                renamed: changes.renamed,
+            },
+            previous: &ctx.previous_snapshot,
+            thresholds: ctx.rule_config(&self.id).thresholds,
+        };
+        let input = serde_json::to_vec(&context).map_err(|e| e.to_string())?;
+
+        let cpu_secs = config.timeout_secs.max(1);
+        let memory = config.memory_mb.saturating_mul(1024 * 1024);
+        let mut cmd = Command::new(&self.path);
+        cmd.current_dir(&ctx.workspace)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        // SAFETY: only async-signal-safe libc calls between fork and exec
+        unsafe {
+            cmd.pre_exec(move || {
+                let limit = |resource, value: u64| {
+                    let rlim = libc::rlimit { rlim_cur: value as libc::rlim_t, rlim_max: value as libc::rlim_t };
+                    libc::setrlimit(resource, &rlim);
+                };
+                limit(libc::RLIMIT_CPU, cpu_secs);
+                limit(libc::RLIMIT_AS, memory);
+                libc::setsid(); // own process group so a timeout kills its children too
+                Ok(())
+            });
+        }
+        let mut child = cmd.spawn().map_err(|e| format!("could not start: {}", e))?;
+
+        // Feed stdin and drain the pipes on threads so a chatty plugin cannot block us
+        let mut stdin = child.stdin.take().ok_or("no stdin")?;
+        let writer = thread::spawn(move || {
+            let _ = stdin.write_all(&input);
+        });
+        let max_output = config.max_output_kb * 1024;
+        let stdout = child.stdout.take().ok_or("no stdout")?;
+        let overflowed = Arc::new(AtomicBool::new(false));
+        let overflow_flag = overflowed.clone();
+        let reader = thread::spawn(move || {
+            let mut stdout = stdout;
+            let mut buf = vec![];
+            let _ = (&mut stdout).take(max_output + 1).read_to_end(&mut buf);
+            if buf.len() as u64 > max_output {
+                // Keep draining until the plugin is killed, so it is never blocked on a full pipe
+                overflow_flag.store(true, Ordering::Relaxed);
+                let _ = io::copy(&mut stdout, &mut io::sink());
+            }
+            buf
+        });
+        let stderr = child.stderr.take().ok_or("no stderr")?;
+        let err_reader = thread::spawn(move || {
+            let mut stderr = stderr;
+            let mut buf = String::new();
+            let _ = (&mut stderr).take(MAX_STDERR).read_to_string(&mut buf);
+            let _ = io::copy(&mut stderr, &mut io::sink());
+            buf
+        });
+
+        let deadline = Instant::now() + Duration::from_secs(config.timeout_secs);
+        let kill = |child: &mut std::process::Child| {
+            unsafe { libc::kill(-(child.id() as i32), libc::SIGKILL) };
+            let _ = child.kill();
+            let _ = child.wait();
+        };
+        let status = loop {
+            match child.try_wait().map_err(|e| e.to_string())? {
+                Some(status) => break status,
+                None if overflowed.load(Ordering::Relaxed) => {
+                    kill(&mut child);
+                    return Err(format!("output exceeds {}KB", config.max_output_kb));
+                }
+                None if Instant::now() >= deadline => {
+                    kill(&mut child);
+                    return Err(format!("timed out after {}s", config.timeout_secs));
+                }
+                None => thread::sleep(Duration::from_millis(50)),
+            }
+        };
+        // Background children left behind may still hold the pipes open; the
+        // group outlives its leader, so this cannot hit an unrelated process
+        unsafe { libc::kill(-(child.id() as i32), libc::SIGKILL) };
+        let _ = writer.join();
+        let output = reader.join().unwrap_or_default();
+        let errors = err_reader.join().unwrap_or_default();
+
+        if !status.success() {
+            let detail = errors.lines().last().unwrap_or("").trim().to_string();
+            return Err(format!("exited with {}{}", status, if detail.is_empty() { String::new() } else { format!(": {}", detail) }));
+        }
+        if output.len() as u64 > max_output {
+            return Err(format!("output exceeds {}KB", config.max_output_kb));
+        }
+
+        let response: PluginResponse = serde_json::from_slice(&output)
+            .map_err(|e| format!("invalid response: {}", e))?;
+        response.validate().map_err(|e| format!("invalid response: {}", e))?;
+        Ok(response)
+    }
+}
+
+impl AuditRule for PluginRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn run(&self, ctx: &SentryAudit) -> (Vec<AuditFinding>, Vec<AuditRecommendation>) {
+        match self.execute(ctx) {
+            Ok(response) => (
+                response.findings.into_iter()
+                    .map(|f| AuditFinding::new(f.title, f.message, f.severity).with_paths(f.paths))
+                    .collect(),
+                response.recommendations.into_iter()
+                    .map(|r| AuditRecommendation::new(r.action))
+                    .collect(),
+            ),
+            Err(e) => (
+                vec![AuditFinding::new("Plugin Failed", format!("{}: {}", self.path.display(), e), Severity::Warning)],
+                vec![],
+            ),
+        }
+    }
+}
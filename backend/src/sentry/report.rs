@@ -0,0 +1,161 @@
+use std::time::Duration;
+
+pub use crate::models::{AuditFinding, AuditRecommendation, SentryReport, Severity};
+
+// --- Structured Reports ---
+//
+// Rules produce findings without a rule id or timestamp; `SentryAudit::evaluate`
+// stamps both before the report is printed and posted to the server.
+
+pub const DEFAULT_REPORT_URL: &str = "http://localhost:8000/api/sentry/reports";
+const POST_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl OutputFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "text" => Some(OutputFormat::Text),
+            "json" => Some(OutputFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+impl Severity {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "ok" => Some(Severity::Ok),
+            "info" => Some(Severity::Info),
+            "warning" => Some(Severity::Warning),
+            "critical" => Some(Severity::Critical),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Ok => "ok",
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Critical => "critical",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Severity::Critical => "🚨 [CRITICAL]",
+            Severity::Warning => "⚠️ [WARNING]",
+            Severity::Info => "ℹ️ [INFO]",
+            Severity::Ok => "✅ [OK]",
+        }
+    }
+}
+
+impl AuditFinding {
+    pub fn new(title: impl Into<String>, message: impl Into<String>, severity: Severity) -> Self {
+        Self {
+            rule_id: String::new(),
+            title: title.into(),
+            message: message.into(),
+            severity,
+            timestamp: String::new(),
+            paths: vec![],
+        }
+    }
+
+    /// Attributes a finding raised outside the rule set (e.g. a watcher notice).
+    pub fn with_rule(mut self, rule_id: &str) -> Self {
+        self.rule_id = rule_id.to_string();
+        self
+    }
+
+    pub fn with_paths(mut self, paths: Vec<String>) -> Self {
+        self.paths = paths;
+        self
+    }
+}
+
+impl AuditRecommendation {
+    pub fn new(action: impl Into<String>) -> Self {
+        Self { rule_id: String::new(), action: action.into() }
+    }
+}
+
+impl SentryReport {
+    /// Findings at warning level or above, most severe first.
+    pub fn alerts(&self) -> Vec<AuditFinding> {
+        let mut alerts: Vec<AuditFinding> = self.findings.iter()
+            .filter(|f| f.severity >= Severity::Warning)
+            .cloned()
+            .collect();
+        alerts.sort_by_key(|f| std::cmp::Reverse(f.severity));
+        alerts
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![];
+        lines.push("=== PROJECT SENTRY AUDIT REPORT ===".to_string());
+        let date = chrono::DateTime::parse_from_rfc3339(&self.timestamp)
+            .map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|_| self.timestamp.clone());
+        lines.push(format!("Date: {}", date));
+        lines.push(format!("Workspace: {}", self.workspace));
+        lines.push("".to_string());
+
+        lines.push("[FINDINGS]".to_string());
+        if self.findings.is_empty() {
+            lines.push("- No significant findings.".to_string());
+        } else {
+            for f in &self.findings {
+                lines.push(format!("{} {}: {}", f.severity.label(), f.title, f.message));
+            }
+        }
+        lines.push("".to_string());
+
+        if !self.recommendations.is_empty() {
+            lines.push("[RECOMMENDED ACTIONS]".to_string());
+            for r in &self.recommendations {
+                lines.push(format!("- {}", r.action));
+            }
+            lines.push("".to_string());
+        }
+
+        lines.push("Roger. Over.".to_string());
+        lines.join("\n")
+    }
+
+    /// One line of JSON per report so daemon output can be consumed as NDJSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn render(&self, format: OutputFormat) -> String {
+        match format {
+            OutputFormat::Text => self.to_text(),
+            OutputFormat::Json => self.to_json(),
+        }
+    }
+}
+
+/// Sends the report to the Mission Control server, which keeps the history.
+pub fn post_report(url: &str, report: &SentryReport) -> Result<(), String> {
+    post_json(url, report)
+}
+
+pub fn post_json<T: serde::Serialize + ?Sized>(url: &str, body: &T) -> Result<(), String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(POST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let resp = client.post(url).json(body).send().map_err(|e| e.to_string())?;
+    if !resp.status().is_success() {
+        return Err(format!("server responded with {}", resp.status()));
+    }
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use std::path::Path;
+
+use crate::artifacts::scan_artifacts;
+use crate::sentry::{AuditFinding, AuditRecommendation, AuditRule, SentryAudit, Severity};
+
+const MAX_LISTED: usize = 10;
+
+fn gb(bytes: u64) -> f64 {
+    bytes as f64 / 1e9
+}
+
+pub struct ArtifactsRule;
+impl AuditRule for ArtifactsRule {
+    fn id(&self) -> &'static str {
+        "artifacts"
+    }
+
+    fn description(&self) -> &'static str {
+        "Size of build artifacts and dependency caches, with stale projects as reclaimable candidates."
+    }
+
+    fn thresholds(&self) -> &'static [(&'static str, f64)] {
+        &[("stale_days", 30.0), ("min_size_mb", 100.0), ("warning_reclaimable_gb", 10.0)]
+    }
+
+    fn full_scan_only(&self) -> bool {
+        true
+    }
+
+    fn run(&self, ctx: &SentryAudit) -> (Vec<AuditFinding>, Vec<AuditRecommendation>) {
+        let mut findings = vec![];
+        let mut recs = vec![];
+
+        let stale_days = ctx.threshold(self, "stale_days") as u64;
+        let min_size = (ctx.threshold(self, "min_size_mb") * 1e6) as u64;
+        let dirs = scan_artifacts(&ctx.workspace, stale_days);
+        if dirs.is_empty() {
+            return (findings, recs);
+        }
+
+        let total: u64 = dirs.iter().map(|d| d.size).sum();
+        let stale: Vec<_> = dirs.iter().filter(|d| d.reclaimable && d.size >= min_size).collect();
+        let reclaimable: u64 = stale.iter().map(|d| d.size).sum();
+        let severity = if gb(reclaimable) >= ctx.threshold(self, "warning_reclaimable_gb") { Severity::Warning }
+                      else if reclaimable > 0 { Severity::Info }
+                      else { Severity::Ok };
+
+        findings.push(AuditFinding::new(
+            "Build Artifacts",
+            format!("{} artifact directories use {:.1}GB; {:.1}GB in {} projects idle for {}+ days.",
+                dirs.len(), gb(total), gb(reclaimable), stale.len(), stale_days),
+            severity,
+        ));
+
+        if !stale.is_empty() {
+            let listed: Vec<String> = stale.iter()
+                .take(MAX_LISTED)
+                .map(|d| format!("{} ({:.1}GB, idle {}d)", ctx.relative_path(Path::new(&d.path)), gb(d.size), d.idle_days))
+                .collect();
+            findings.push(AuditFinding::new(
+                "Reclaimable Artifacts",
+                listed.join(", "),
+                Severity::Info,
+            ).with_paths(stale.iter().map(|d| ctx.relative_path(Path::new(&d.path))).collect()));
+            recs.push(AuditRecommendation::new(format!(
+                "Remove {:.1}GB of stale build artifacts via the disk cleanup view.", gb(reclaimable))));
+        }
+
+        (findings, recs)
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use crate::sentry::{AuditFinding, AuditRecommendation, AuditRule, SentryAudit, Severity};
+
+/// Maximum number of paths listed per directory finding.
+const MAX_LISTED: usize = 10;
+
+pub struct ChurnRule;
+impl AuditRule for ChurnRule {
+    fn id(&self) -> &'static str {
+        "churn"
+    }
+
+    fn description(&self) -> &'static str {
+        "Files created, modified, deleted or moved since the last audit."
+    }
+
+    fn thresholds(&self) -> &'static [(&'static str, f64)] {
+        &[("warning_changes", 50.0), ("duplicate_mb", 100.0)]
+    }
+
+    fn run(&self, ctx: &SentryAudit) -> (Vec<AuditFinding>, Vec<AuditRecommendation>) {
+        let mut findings = vec![];
+        let mut recs = vec![];
+
+        let changes = ctx.changes();
+        let churn = changes.churn();
+        if churn > 0 {
+            let severity = if churn as f64 > ctx.threshold(self, "warning_changes") { Severity::Warning } else { Severity::Info };
+            findings.push(AuditFinding::new(
+                "File Churn",
+                format!("{} changes since last audit (+{} ~{} -{} >{}).",
+                    churn, changes.created.len(), changes.modified.len(), changes.deleted.len(), changes.renamed.len()),
+                severity,
+            ));
+
+            for (dir, stats) in changes.by_top_dir() {
+                let mut listed: Vec<String> = stats.changes.iter()
+                    .take(MAX_LISTED)
+                    .map(|(kind, path)| format!("{}{}", kind.marker(), path))
+                    .collect();
+                if stats.changes.len() > MAX_LISTED {
+                    listed.push(format!("… and {} more", stats.changes.len() - MAX_LISTED));
+                }
+                let paths = stats.changes.iter().map(|(_, path)| path.clone()).collect();
+                findings.push(AuditFinding::new(
+                    format!("Churn in {}", dir),
+                    format!("{} changes (+{} ~{} -{} >{}): {}",
+                        stats.total(), stats.created, stats.modified, stats.deleted, stats.renamed, listed.join(", ")),
+                    Severity::Info,
+                ).with_paths(paths));
+            }
+        }
+
+        if !changes.touched.is_empty() {
+            findings.push(AuditFinding::new(
+                "Touched Files",
+                format!("{} files have a new mtime but identical content.", changes.touched.len()),
+                Severity::Info,
+            ).with_paths(changes.touched.clone()));
+        }
+
+        // Identical non-empty content stored under several paths
+        let mut by_hash: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (path, meta) in &ctx.current_snapshot {
+            if let Some(hash) = &meta.hash && meta.size > 0 {
+                by_hash.entry(hash.as_str()).or_default().push(path);
+            }
+        }
+        let duplicates: Vec<&Vec<&str>> = by_hash.values().filter(|v| v.len() > 1).collect();
+        if !duplicates.is_empty() {
+            let wasted: u64 = duplicates.iter()
+                .map(|paths| ctx.current_snapshot[paths[0]].size * (paths.len() as u64 - 1))
+                .sum();
+            let wasted_mb = wasted as f64 / (1024.0 * 1024.0);
+            let paths = duplicates.iter().flat_map(|group| group.iter().map(|p| p.to_string())).collect();
+            findings.push(AuditFinding::new(
+                "Duplicate Content",
+                format!("{} groups of identical files ({:.1}MB redundant).", duplicates.len(), wasted_mb),
+                Severity::Info,
+            ).with_paths(paths));
+            if wasted_mb > ctx.threshold(self, "duplicate_mb") {
+                recs.push(AuditRecommendation::new("Deduplicate large identical files."));
+            }
+        }
+
+        (findings, recs)
+    }
+}
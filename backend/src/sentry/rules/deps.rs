@@ -0,0 +1,193 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::sentry::advisories::{Advisory, AdvisoryDb, Ecosystem};
+use crate::sentry::{AuditFinding, AuditRecommendation, AuditRule, SentryAudit, Severity};
+
+// --- Dependency Vulnerabilities ---
+//
+// Checks every `Cargo.lock` and `package-lock.json` in the snapshot against
+// the offline advisory mirror (the snapshot keeps lockfiles even when they
+// are gitignored). Path and git dependencies in Cargo.lock and
+// linked packages in package-lock.json are not published, so they are skipped.
+
+#[derive(Debug, Deserialize)]
+struct CargoLock {
+    #[serde(default)]
+    package: Vec<CargoPackage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CargoPackage {
+    name: String,
+    version: String,
+    source: Option<String>,
+}
+
+fn cargo_packages(content: &str) -> Vec<(String, String)> {
+    let Ok(lock) = toml::from_str::<CargoLock>(content) else { return vec![] };
+    lock.package.into_iter()
+        .filter(|p| p.source.as_deref().is_some_and(|s| s.starts_with("registry+") || s.starts_with("sparse+")))
+        .map(|p| (p.name, p.version))
+        .collect()
+}
+
+/// Installed packages from lockfile v2/v3 `packages`, or the nested v1 `dependencies`.
+fn npm_packages(content: &str) -> Vec<(String, String)> {
+    let Ok(lock) = serde_json::from_str::<serde_json::Value>(content) else { return vec![] };
+    let mut found = vec![];
+    if let Some(packages) = lock.get("packages").and_then(|p| p.as_object()) {
+        for (key, pkg) in packages {
+            let Some(name) = key.rsplit("node_modules/").next().filter(|n| !key.is_empty() && !n.is_empty()) else { continue };
+            if pkg.get("link").and_then(|l| l.as_bool()) == Some(true) {
+                continue;
+            }
+            if let Some(version) = pkg.get("version").and_then(|v| v.as_str()) {
+                found.push((name.to_string(), version.to_string()));
+            }
+        }
+    } else {
+        fn walk(deps: &serde_json::Value, found: &mut Vec<(String, String)>) {
+            let Some(deps) = deps.as_object() else { return };
+            for (name, dep) in deps {
+                if let Some(version) = dep.get("version").and_then(|v| v.as_str()) {
+                    found.push((name.clone(), version.to_string()));
+                }
+                if let Some(nested) = dep.get("dependencies") {
+                    walk(nested, found);
+                }
+            }
+        }
+        if let Some(deps) = lock.get("dependencies") {
+            walk(deps, &mut found);
+        }
+    }
+    found
+}
+
+/// A vulnerable package version and where it is pinned.
+#[derive(Default)]
+struct Hit<'a> {
+    advisories: Vec<&'a Advisory>,
+    lockfiles: BTreeSet<&'a str>,
+}
+
+fn describe(advisory: &Advisory) -> String {
+    let mut text = advisory.id.clone();
+    if !advisory.aliases.is_empty() {
+        text.push_str(&format!(" ({})", advisory.aliases.join(", ")));
+    }
+    if !advisory.title.is_empty() {
+        text.push_str(&format!(": {}", advisory.title));
+    }
+    text.push_str(&match &advisory.fixed {
+        Some(fixed) => format!("; fixed in {}", fixed),
+        None => "; no fixed version listed".to_string(),
+    });
+    text
+}
+
+pub struct DepsRule;
+impl AuditRule for DepsRule {
+    fn id(&self) -> &'static str {
+        "deps"
+    }
+
+    fn description(&self) -> &'static str {
+        "Cargo.lock and package-lock.json entries with known advisories (offline RustSec / npm mirror)."
+    }
+
+    fn full_scan_only(&self) -> bool {
+        true
+    }
+
+    fn run(&self, ctx: &SentryAudit) -> (Vec<AuditFinding>, Vec<AuditRecommendation>) {
+        let mut findings = vec![];
+        let mut recs = vec![];
+
+        let lockfiles: Vec<&String> = ctx.current_snapshot.keys()
+            .filter(|p| {
+                let name = p.rsplit('/').next().unwrap_or(p);
+                name == "Cargo.lock" || name == "package-lock.json"
+            })
+            .collect();
+        if lockfiles.is_empty() {
+            return (findings, recs);
+        }
+
+        let config = &ctx.config.advisories;
+        let db = AdvisoryDb::load(config);
+        if db.is_empty() {
+            findings.push(AuditFinding::new(
+                "Advisory Database Missing",
+                format!("No advisories found in {} or {}; {} lockfiles were not checked.",
+                    config.rustsec_dir, config.npm_db, lockfiles.len()),
+                Severity::Info,
+            ));
+            recs.push(AuditRecommendation::new(format!(
+                "Mirror the RustSec advisory-db to {} (e.g. `cargo audit fetch`).", config.rustsec_dir)));
+            return (findings, recs);
+        }
+
+        // Keyed by (ecosystem label, package, version)
+        let mut vulnerable: BTreeMap<(&str, String, String), Hit> = BTreeMap::new();
+        let mut checked = 0;
+        for lockfile in &lockfiles {
+            let Ok(content) = fs::read_to_string(ctx.workspace.join(lockfile)) else { continue };
+            let (ecosystem, label, packages) = if lockfile.ends_with("Cargo.lock") {
+                (Ecosystem::Cargo, "crate", cargo_packages(&content))
+            } else {
+                (Ecosystem::Npm, "npm package", npm_packages(&content))
+            };
+            let packages: BTreeSet<(String, String)> = packages.into_iter().collect();
+            checked += packages.len();
+            for (name, version) in packages {
+                let matches = db.matching(ecosystem, &name, &version);
+                if matches.is_empty() {
+                    continue;
+                }
+                let entry = vulnerable.entry((label, name, version)).or_default();
+                for advisory in matches {
+                    if !entry.advisories.iter().any(|a| a.id == advisory.id) {
+                        entry.advisories.push(advisory);
+                    }
+                }
+                entry.lockfiles.insert(lockfile.as_str());
+            }
+        }
+
+        findings.push(AuditFinding::new(
+            "Dependency Audit",
+            format!("{} packages in {} lockfiles checked against {} advisories; {} vulnerable.",
+                checked, lockfiles.len(), db.len(), vulnerable.len()),
+            if vulnerable.is_empty() { Severity::Ok } else { Severity::Warning },
+        ));
+
+        for ((label, name, version), hit) in &vulnerable {
+            let advisories = &hit.advisories;
+            let severity = advisories.iter().map(|a| a.severity).max().unwrap_or_default();
+            let details: Vec<String> = advisories.iter().map(|a| describe(a)).collect();
+            let files: Vec<String> = hit.lockfiles.iter().map(|f| f.to_string()).collect();
+            findings.push(AuditFinding::new(
+                format!("Vulnerable {}: {} {}", label, name, version),
+                format!("{} (in {})", details.join(" | "), files.join(", ")),
+                severity,
+            ).with_paths(files.clone()));
+
+            if severity >= Severity::Warning {
+                let fixed: Vec<&str> = advisories.iter().filter_map(|a| a.fixed.as_deref()).collect();
+                let dir = Path::new(&files[0]).parent().map(|d| d.display().to_string()).filter(|d| !d.is_empty()).unwrap_or_else(|| ".".into());
+                recs.push(AuditRecommendation::new(match (*label, fixed.is_empty()) {
+                    (_, true) => format!("Upgrade or replace {} {} in {}; the advisory lists no fixed version.", name, version, dir),
+                    ("crate", false) => format!("Run `cargo update -p {}` in {} (fixed in {}).", name, dir, fixed.join("; ")),
+                    _ => format!("Run `npm update {}` in {} (fixed in {}).", name, dir, fixed.join("; ")),
+                }));
+            }
+        }
+
+        (findings, recs)
+    }
+}
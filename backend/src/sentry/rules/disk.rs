@@ -0,0 +1,94 @@
+use crate::mounts::{level, mount_stats, DiskConfig};
+use crate::sentry::{AuditFinding, AuditRecommendation, AuditRule, SentryAudit, Severity};
+
+pub struct DiskRule;
+impl AuditRule for DiskRule {
+    fn id(&self) -> &'static str {
+        "disk"
+    }
+
+    fn description(&self) -> &'static str {
+        "Space, inode usage and read-only state of the watched mounts (thresholds in disk_config.json unless set here)."
+    }
+
+    /// Mirror the disk_config.json defaults; a value set in the Sentry config
+    /// replaces the disk_config.json default, per-mount thresholds still win.
+    fn thresholds(&self) -> &'static [(&'static str, f64)] {
+        &[("warning_pct", 85.0), ("critical_pct", 90.0), ("inode_warning_pct", 85.0), ("inode_critical_pct", 95.0)]
+    }
+
+    fn full_scan_only(&self) -> bool {
+        true
+    }
+
+    fn run(&self, ctx: &SentryAudit) -> (Vec<AuditFinding>, Vec<AuditRecommendation>) {
+        let mut findings = vec![];
+        let mut recs = vec![];
+
+        // Same mounts as the dashboard
+        let mut config = DiskConfig::load();
+        let overrides = ctx.rule_config(self.id()).thresholds;
+        for (key, value) in [
+            ("warning_pct", &mut config.warning_pct),
+            ("critical_pct", &mut config.critical_pct),
+            ("inode_warning_pct", &mut config.inode_warning_pct),
+            ("inode_critical_pct", &mut config.inode_critical_pct),
+        ] {
+            if let Some(v) = overrides.get(key) {
+                *value = *v;
+            }
+        }
+        let mounts = mount_stats(&config);
+        if mounts.is_empty() {
+            findings.push(AuditFinding::new(
+                "Disk Detection",
+                "None of the watched mounts could be found.",
+                Severity::Warning,
+            ));
+        }
+
+        for mount in mounts {
+            if !mount.mounted {
+                findings.push(AuditFinding::new(
+                    format!("Mount Missing: {}", mount.label),
+                    format!("{} is watched but not mounted.", mount.mount_point),
+                    Severity::Warning,
+                ).with_paths(vec![mount.mount_point.clone()]));
+                continue;
+            }
+
+            let space = level(mount.usage_pct, mount.warning_pct, mount.critical_pct);
+            findings.push(AuditFinding::new(
+                format!("Disk Usage: {}", mount.label),
+                format!("{:.1}% used ({:.1}GB/{:.1}GB) on {} ({})", mount.usage_pct, mount.used as f64 / 1e9, mount.total as f64 / 1e9, mount.mount_point, mount.fs_type),
+                space,
+            ));
+            if space > Severity::Ok {
+                recs.push(AuditRecommendation::new(format!("Run disk cleanup to free up space on {}.", mount.mount_point)));
+            }
+
+            let inodes = level(mount.inode_pct, mount.inode_warning_pct, mount.inode_critical_pct);
+            if inodes > Severity::Ok {
+                findings.push(AuditFinding::new(
+                    format!("Inode Usage: {}", mount.label),
+                    format!("{:.1}% of inodes used ({}/{}) on {}", mount.inode_pct, mount.inodes_used, mount.inodes_total, mount.mount_point),
+                    inodes,
+                ));
+                recs.push(AuditRecommendation::new(format!(
+                    "Remove directories with many small files on {} (node_modules, caches, build output).",
+                    mount.mount_point
+                )));
+            }
+
+            if mount.read_only && !mount.allow_read_only {
+                findings.push(AuditFinding::new(
+                    format!("Read-only Mount: {}", mount.label),
+                    format!("{} is mounted read-only; the kernel may have remounted it after I/O errors.", mount.mount_point),
+                    Severity::Warning,
+                ));
+            }
+        }
+
+        (findings, recs)
+    }
+}
@@ -0,0 +1,212 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use chrono::Local;
+use walkdir::WalkDir;
+
+use crate::sentry::{AuditFinding, AuditRecommendation, AuditRule, SentryAudit, Severity};
+
+// --- Git Health ---
+//
+// Reports on every repository below the workspace using only local data
+// (no fetch), so ahead/behind reflects the last time the remote was fetched.
+
+const MAX_LISTED: usize = 5;
+const DEFAULT_INTERVAL_SECS: u64 = 300;
+
+fn git(repo: &Path, args: &[&str]) -> Option<String> {
+    let out = Command::new("git").args(args).current_dir(repo).output().ok()?;
+    out.status.success().then(|| String::from_utf8_lossy(&out.stdout).to_string())
+}
+
+#[derive(Debug, Default)]
+struct RepoHealth {
+    branch: Option<String>, // None when HEAD is detached
+    upstream: Option<String>,
+    modified: usize,
+    untracked: usize,
+    conflicted: usize,
+    ahead: usize,
+    behind: usize,
+    unpushed: usize, // commits on no remote at all, for branches without an upstream
+    stashes: usize,
+    merged: Vec<String>,
+    last_commit: Option<i64>, // Unix seconds
+}
+
+impl RepoHealth {
+    fn inspect(repo: &Path) -> Option<Self> {
+        let status = git(repo, &["status", "--porcelain=v2", "--branch", "--untracked-files=normal"])?;
+        let mut health = RepoHealth::default();
+
+        for line in status.lines() {
+            if let Some(head) = line.strip_prefix("# branch.head ") {
+                health.branch = (head != "(detached)").then(|| head.to_string());
+            } else if let Some(upstream) = line.strip_prefix("# branch.upstream ") {
+                health.upstream = Some(upstream.to_string());
+            } else if let Some(ab) = line.strip_prefix("# branch.ab ") {
+                let mut parts = ab.split_whitespace();
+                health.ahead = parts.next().and_then(|a| a.trim_start_matches('+').parse().ok()).unwrap_or(0);
+                health.behind = parts.next().and_then(|b| b.trim_start_matches('-').parse().ok()).unwrap_or(0);
+            } else if line.starts_with("1 ") || line.starts_with("2 ") {
+                health.modified += 1;
+            } else if line.starts_with("u ") {
+                health.conflicted += 1;
+            } else if line.starts_with("? ") {
+                health.untracked += 1;
+            }
+        }
+
+        let has_remotes = git(repo, &["remote"]).is_some_and(|r| !r.trim().is_empty());
+        if health.upstream.is_none() && health.branch.is_some() && has_remotes {
+            health.unpushed = git(repo, &["rev-list", "--count", "HEAD", "--not", "--remotes"])
+                .and_then(|c| c.trim().parse().ok())
+                .unwrap_or(0);
+        }
+
+        health.stashes = git(repo, &["stash", "list"]).map(|s| s.lines().count()).unwrap_or(0);
+        health.last_commit = git(repo, &["log", "-1", "--format=%ct"]).and_then(|t| t.trim().parse().ok());
+
+        // Branches already merged into the main line (or HEAD when there is none)
+        let base = ["main", "master"].into_iter()
+            .find(|b| git(repo, &["rev-parse", "--verify", "--quiet", &format!("refs/heads/{}", b)]).is_some());
+        let merged = git(repo, &["branch", "--merged", base.unwrap_or("HEAD"), "--format=%(refname:short)"]).unwrap_or_default();
+        health.merged = merged.lines()
+            .map(|b| b.trim().to_string())
+            .filter(|b| !b.is_empty() && !b.starts_with('(') && Some(b.as_str()) != base && Some(b) != health.branch.as_ref())
+            .collect();
+
+        Some(health)
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.modified + self.untracked + self.conflicted > 0
+    }
+
+    fn unpushed_commits(&self) -> usize {
+        self.ahead + self.unpushed
+    }
+}
+
+fn fmt_age(secs: i64) -> String {
+    match secs {
+        s if s < 3600 => format!("{} min", s / 60),
+        s if s < 86400 => format!("{} hours", s / 3600),
+        s => format!("{} days", s / 86400),
+    }
+}
+
+/// Walks the workspace for repositories, pruning ignored trees. Costly on
+/// large workspaces, hence the rule's default interval.
+fn discover(ctx: &SentryAudit) -> Vec<PathBuf> {
+    WalkDir::new(&ctx.workspace).into_iter()
+        .filter_entry(|e| e.file_name() != ".git" && !ctx.is_ignored_entry(e.path(), e.file_type().is_dir()))
+        .flatten()
+        .filter(|e| e.file_type().is_dir() && e.path().join(".git").exists())
+        .map(|e| e.into_path())
+        .collect()
+}
+
+pub struct GitRule;
+impl AuditRule for GitRule {
+    fn id(&self) -> &'static str {
+        "git"
+    }
+
+    fn description(&self) -> &'static str {
+        "Health of every git repository in the workspace (local data only, no fetch)."
+    }
+
+    fn thresholds(&self) -> &'static [(&'static str, f64)] {
+        &[("stale_days", 30.0)]
+    }
+
+    fn default_interval(&self) -> Option<u64> {
+        Some(DEFAULT_INTERVAL_SECS)
+    }
+
+    fn run(&self, ctx: &SentryAudit) -> (Vec<AuditFinding>, Vec<AuditRecommendation>) {
+        let mut findings = vec![];
+        let mut recs = vec![];
+
+        let repos = discover(ctx);
+        if repos.is_empty() {
+            return (findings, recs);
+        }
+        let now = Local::now().timestamp();
+        let stale_secs = (ctx.threshold(self, "stale_days") * 86400.0) as i64;
+        let mut needs_attention = 0;
+
+        for repo in &repos {
+            let Some(health) = RepoHealth::inspect(repo) else { continue };
+            let rel = match ctx.relative_path(repo) {
+                p if p.is_empty() => ".".to_string(),
+                p => p,
+            };
+
+            let mut parts = vec![match &health.branch {
+                Some(branch) => format!("on {}", branch),
+                None => "detached HEAD".to_string(),
+            }];
+            if health.is_dirty() {
+                parts.push(format!("{} modified, {} untracked", health.modified, health.untracked));
+            }
+            if health.conflicted > 0 {
+                parts.push(format!("{} conflicted", health.conflicted));
+            }
+            if let Some(upstream) = &health.upstream {
+                if health.ahead + health.behind > 0 {
+                    parts.push(format!("ahead {} / behind {} of {}", health.ahead, health.behind, upstream));
+                }
+            } else if health.unpushed > 0 {
+                parts.push(format!("{} commits on no remote", health.unpushed));
+            }
+            if health.stashes > 0 {
+                parts.push(format!("{} stashes", health.stashes));
+            }
+            if !health.merged.is_empty() {
+                let listed: Vec<&str> = health.merged.iter().take(MAX_LISTED).map(|b| b.as_str()).collect();
+                parts.push(format!("merged branches: {}", listed.join(", ")));
+            }
+            let stale = health.last_commit.is_some_and(|t| now - t > stale_secs);
+            if let Some(t) = health.last_commit {
+                parts.push(format!("last commit {} ago", fmt_age(now - t)));
+            }
+
+            let severity = if health.conflicted > 0 || health.is_dirty() || health.unpushed_commits() > 0 || health.branch.is_none() {
+                Severity::Warning
+            } else if health.stashes > 0 || health.behind > 0 || !health.merged.is_empty() || stale {
+                Severity::Info
+            } else {
+                Severity::Ok
+            };
+            if severity >= Severity::Warning {
+                needs_attention += 1;
+            }
+
+            findings.push(AuditFinding::new(format!("Git: {}", rel), parts.join("; "), severity)
+                .with_paths(vec![rel.clone()]));
+
+            if health.is_dirty() {
+                recs.push(AuditRecommendation::new(format!("Commit or stash changes in {}.", rel)));
+            }
+            if health.unpushed_commits() > 0 {
+                recs.push(AuditRecommendation::new(format!("Push {} unpushed commits in {}.", health.unpushed_commits(), rel)));
+            }
+            if health.branch.is_none() {
+                recs.push(AuditRecommendation::new(format!("Check out a branch in {} (HEAD is detached).", rel)));
+            }
+            if !health.merged.is_empty() {
+                recs.push(AuditRecommendation::new(format!("Delete {} merged branches in {}.", health.merged.len(), rel)));
+            }
+        }
+
+        findings.insert(0, AuditFinding::new(
+            "Git Repositories",
+            format!("{} repositories found, {} need attention.", repos.len(), needs_attention),
+            if needs_attention > 0 { Severity::Warning } else { Severity::Ok },
+        ));
+
+        (findings, recs)
+    }
+}
@@ -0,0 +1,164 @@
+use std::path::Path;
+
+use chrono::Local;
+use sysinfo::Disks;
+
+use crate::sentry::snapshot::GROWTH_SAMPLE_INTERVAL;
+use crate::sentry::{AuditFinding, AuditRecommendation, AuditRule, SentryAudit, Severity};
+
+// --- Large Files & Growth Trends ---
+//
+// Compares the current snapshot against the sampled size history kept in the
+// Sentry state file. Trends need at least one full sample interval of data.
+
+const MAX_LISTED: usize = 10;
+/// Estimates further out than this are not worth reporting.
+const MAX_REPORTED_ETA_HOURS: f64 = 365.0 * 24.0;
+
+fn fmt_bytes(bytes: f64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes;
+    let mut unit = 0;
+    while value.abs() >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1}{}", value, UNITS[unit])
+}
+
+fn fmt_hours(hours: f64) -> String {
+    if hours < 48.0 {
+        format!("{:.1} hours", hours)
+    } else {
+        format!("{:.1} days", hours / 24.0)
+    }
+}
+
+/// Free bytes on the partition holding `path` (longest matching mount point).
+fn partition_free(path: &Path) -> Option<u64> {
+    let disks = Disks::new_with_refreshed_list();
+    disks.iter()
+        .filter(|d| path.starts_with(d.mount_point()))
+        .max_by_key(|d| d.mount_point().as_os_str().len())
+        .map(|d| d.available_space())
+}
+
+fn cleanup_advice(path: &str) -> String {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    if name.ends_with(".log") || name.contains(".log.") {
+        format!("Rotate or truncate {} (e.g. with logrotate).", path)
+    } else if path.starts_with("isolated_data/") || path.contains("/isolated_data/") {
+        format!("Clear the browser profile cache holding {}.", path)
+    } else {
+        format!("Review {} as a cleanup candidate.", path)
+    }
+}
+
+pub struct GrowthRule;
+impl AuditRule for GrowthRule {
+    fn id(&self) -> &'static str {
+        "growth"
+    }
+
+    fn description(&self) -> &'static str {
+        "New large files, fast-growing files and time until the partition is full."
+    }
+
+    fn thresholds(&self) -> &'static [(&'static str, f64)] {
+        &[("large_file_mb", 100.0), ("growth_mb_per_hour", 50.0), ("ttf_warning_hours", 168.0), ("ttf_critical_hours", 24.0)]
+    }
+
+    fn run(&self, ctx: &SentryAudit) -> (Vec<AuditFinding>, Vec<AuditRecommendation>) {
+        let mut findings = vec![];
+        let mut recs = vec![];
+
+        let large_threshold = (ctx.threshold(self, "large_file_mb") * 1024.0 * 1024.0) as u64;
+        let growth_limit = ctx.threshold(self, "growth_mb_per_hour") * 1024.0 * 1024.0;
+
+        // New large files
+        let changes = ctx.changes();
+        let mut large: Vec<(&String, u64)> = changes.created.iter()
+            .filter_map(|p| ctx.current_snapshot.get(p).map(|m| (p, m.size)))
+            .filter(|(_, size)| *size >= large_threshold)
+            .collect();
+        large.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+        if !large.is_empty() {
+            let listed: Vec<String> = large.iter()
+                .take(MAX_LISTED)
+                .map(|(p, size)| format!("{} ({})", p, fmt_bytes(*size as f64)))
+                .collect();
+            findings.push(AuditFinding::new(
+                "Large New Files",
+                format!("{} files over {} added: {}", large.len(), fmt_bytes(large_threshold as f64), listed.join(", ")),
+                Severity::Warning,
+            ).with_paths(large.iter().map(|(p, _)| p.to_string()).collect()));
+        }
+
+        // Growth over the sampled history
+        let now = Local::now().timestamp().max(0) as u64;
+        let Some(oldest) = ctx.history.first() else {
+            return (findings, recs);
+        };
+        if now < oldest.taken + GROWTH_SAMPLE_INTERVAL {
+            return (findings, recs);
+        }
+
+        let mut growing: Vec<(&String, f64, u64)> = vec![];
+        for (path, meta) in &ctx.current_snapshot {
+            // Baseline is the earliest sample that already knew the file
+            let Some((taken, base)) = ctx.history.iter()
+                .find_map(|s| s.sizes.get(path).map(|size| (s.taken, *size)))
+            else {
+                continue;
+            };
+            let hours = now.saturating_sub(taken) as f64 / 3600.0;
+            if now < taken + GROWTH_SAMPLE_INTERVAL || meta.size <= base {
+                continue;
+            }
+            let rate = (meta.size - base) as f64 / hours;
+            if rate > growth_limit {
+                growing.push((path, rate, meta.size));
+            }
+        }
+        growing.sort_by(|a, b| b.1.total_cmp(&a.1));
+        if !growing.is_empty() {
+            let listed: Vec<String> = growing.iter()
+                .take(MAX_LISTED)
+                .map(|(p, rate, size)| format!("{} (+{}/h, now {})", p, fmt_bytes(*rate), fmt_bytes(*size as f64)))
+                .collect();
+            findings.push(AuditFinding::new(
+                "Fast-growing Files",
+                format!("{} files growing faster than {}/h: {}", growing.len(), fmt_bytes(growth_limit), listed.join(", ")),
+                Severity::Warning,
+            ).with_paths(growing.iter().map(|(p, _, _)| p.to_string()).collect()));
+            for (path, _, _) in growing.iter().take(3) {
+                recs.push(AuditRecommendation::new(cleanup_advice(path)));
+            }
+        }
+
+        // Time until the partition fills at the current workspace growth rate
+        let total: u64 = ctx.current_snapshot.values().map(|m| m.size).sum();
+        let hours = (now - oldest.taken) as f64 / 3600.0;
+        let rate = (total as f64 - oldest.total as f64) / hours;
+        if rate > 0.0 && let Some(free) = partition_free(&ctx.workspace) {
+            let eta = free as f64 / rate;
+            if eta > MAX_REPORTED_ETA_HOURS {
+                return (findings, recs);
+            }
+            let severity = if eta < ctx.threshold(self, "ttf_critical_hours") { Severity::Critical }
+                          else if eta < ctx.threshold(self, "ttf_warning_hours") { Severity::Warning }
+                          else { Severity::Info };
+            findings.push(AuditFinding::new(
+                "Disk Time-to-Full",
+                format!("Workspace grew {}/h over the last {}; {} free lasts ~{}.",
+                    fmt_bytes(rate), fmt_hours(hours), fmt_bytes(free as f64), fmt_hours(eta)),
+                severity,
+            ));
+            if severity >= Severity::Warning && growing.is_empty() {
+                recs.push(AuditRecommendation::new("Run disk cleanup; workspace growth will fill the partition soon."));
+            }
+        }
+
+        (findings, recs)
+    }
+}
@@ -0,0 +1,89 @@
+use std::path::Path;
+
+use crate::sentry::baseline::Baseline;
+use crate::sentry::{AuditFinding, AuditRecommendation, AuditRule, SentryAudit, Severity};
+
+// --- File Integrity ---
+//
+// Compares the designated critical files against the signed baseline. Any
+// drift is critical until it is accepted with `sentry baseline accept`.
+
+pub struct IntegrityRule;
+impl AuditRule for IntegrityRule {
+    fn id(&self) -> &'static str {
+        "integrity"
+    }
+
+    fn description(&self) -> &'static str {
+        "Mode, owner, size and hash of critical files (metadata only for server state) against the signed baseline."
+    }
+
+    fn run(&self, ctx: &SentryAudit) -> (Vec<AuditFinding>, Vec<AuditRecommendation>) {
+        let mut findings = vec![];
+        let mut recs = vec![];
+
+        let config = &ctx.config.baseline;
+        let Some(baseline) = Baseline::load(config) else {
+            if Path::new(&config.file).exists() {
+                findings.push(AuditFinding::new(
+                    "Baseline Unreadable",
+                    format!("{} exists but could not be parsed. The baseline may have been tampered with.", config.file),
+                    Severity::Critical,
+                ).with_paths(vec![config.file.clone()]));
+                return (findings, recs);
+            }
+            findings.push(AuditFinding::new(
+                "No Integrity Baseline",
+                format!("{} critical paths are configured but no baseline exists at {}.", config.paths.len(), config.file),
+                Severity::Info,
+            ));
+            recs.push(AuditRecommendation::new("Run `sentry baseline create` to start verifying critical files."));
+            return (findings, recs);
+        };
+
+        if let Err(e) = baseline.verify(config) {
+            findings.push(AuditFinding::new(
+                "Baseline Signature Invalid",
+                format!("{}: {}. The baseline may have been tampered with.", config.file, e),
+                Severity::Critical,
+            ).with_paths(vec![config.file.clone()]));
+            recs.push(AuditRecommendation::new(format!(
+                "Inspect {} and recreate it with `sentry baseline create` once the files are trusted.", config.file)));
+            return (findings, recs);
+        }
+
+        let mut drifted = 0;
+        for (path, expected) in &baseline.entries {
+            let changes = expected.drift(&config.capture(path));
+            if changes.is_empty() {
+                continue;
+            }
+            drifted += 1;
+            findings.push(AuditFinding::new(
+                format!("Integrity Drift: {}", path),
+                format!("{} since the baseline of {}.", changes.join(", "), baseline.updated),
+                Severity::Critical,
+            ).with_paths(vec![path.clone()]));
+            recs.push(AuditRecommendation::new(format!(
+                "If the change to {} was intended, run `sentry baseline accept {}`.", path, path)));
+        }
+
+        let unbaselined: Vec<&String> = config.paths.iter().filter(|p| !baseline.entries.contains_key(*p)).collect();
+        if !unbaselined.is_empty() {
+            findings.push(AuditFinding::new(
+                "Paths Not Baselined",
+                format!("Configured but missing from the baseline: {}", unbaselined.iter().map(|p| p.as_str()).collect::<Vec<_>>().join(", ")),
+                Severity::Info,
+            ));
+            recs.push(AuditRecommendation::new("Run `sentry baseline accept` to add newly configured paths."));
+        }
+
+        findings.insert(0, AuditFinding::new(
+            "File Integrity",
+            format!("{} of {} baselined paths drifted.", drifted, baseline.entries.len()),
+            if drifted > 0 { Severity::Warning } else { Severity::Ok },
+        ));
+
+        (findings, recs)
+    }
+}
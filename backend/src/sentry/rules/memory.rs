@@ -0,0 +1,49 @@
+use crate::sentry::{AuditFinding, AuditRecommendation, AuditRule, SentryAudit, Severity};
+
+pub struct MemoryRule;
+impl AuditRule for MemoryRule {
+    fn id(&self) -> &'static str {
+        "memory"
+    }
+
+    fn description(&self) -> &'static str {
+        "System RAM usage."
+    }
+
+    fn thresholds(&self) -> &'static [(&'static str, f64)] {
+        &[("warning_pct", 60.0), ("critical_pct", 80.0)]
+    }
+
+    fn run(&self, ctx: &SentryAudit) -> (Vec<AuditFinding>, Vec<AuditRecommendation>) {
+        use sysinfo::System;
+        let mut sys = System::new_all();
+        sys.refresh_memory();
+
+        let total = sys.total_memory();
+        let used = sys.used_memory();
+        let pct = (used as f64 / total as f64) * 100.0;
+        
+        let total_gb = total as f64 / (1024.0 * 1024.0 * 1024.0);
+        let used_gb = used as f64 / (1024.0 * 1024.0 * 1024.0);
+
+        let mut findings = vec![];
+        let mut recs = vec![];
+
+        let warning = ctx.threshold(self, "warning_pct");
+        let severity = if pct > ctx.threshold(self, "critical_pct") { Severity::Critical } 
+                      else if pct > warning { Severity::Warning } 
+                      else { Severity::Ok };
+
+        findings.push(AuditFinding::new(
+            "Memory Usage",
+            format!("{:.1}% used ({:.1}GB/{:.1}GB)", pct, used_gb, total_gb),
+            severity,
+        ));
+
+        if pct > warning {
+            recs.push(AuditRecommendation::new("Check for memory leaks or close unused applications."));
+        }
+
+        (findings, recs)
+    }
+}
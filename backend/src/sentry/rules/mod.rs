@@ -0,0 +1,39 @@
+mod memory;
+mod disk;
+mod git;
+mod churn;
+mod secrets;
+mod growth;
+mod processes;
+mod artifacts;
+mod deps;
+mod integrity;
+
+pub use memory::MemoryRule;
+pub use disk::DiskRule;
+pub use git::GitRule;
+pub use churn::ChurnRule;
+pub use secrets::{SecretMatch, SecretsRule};
+pub use growth::GrowthRule;
+pub use processes::{ProcessConfig, ProcessRule};
+pub use artifacts::ArtifactsRule;
+pub use deps::DepsRule;
+pub use integrity::IntegrityRule;
+
+use crate::sentry::AuditRule;
+
+/// Every built-in rule, in report order.
+pub fn all() -> Vec<Box<dyn AuditRule>> {
+    vec![
+        Box::new(MemoryRule {}),
+        Box::new(IntegrityRule {}),
+        Box::new(GitRule {}),
+        Box::new(ChurnRule {}),
+        Box::new(SecretsRule {}),
+        Box::new(DepsRule {}),
+        Box::new(GrowthRule {}),
+        Box::new(ProcessRule {}),
+        Box::new(DiskRule {}),
+        Box::new(ArtifactsRule {}),
+    ]
+}
@@ -0,0 +1,197 @@
+use std::collections::BTreeMap;
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, Process, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, Signal, System, UpdateKind};
+
+use crate::sentry::{AuditFinding, AuditRecommendation, AuditRule, SentryAudit, Severity};
+
+// --- Processes & Runaway Browsers ---
+//
+// The bridge starts one Chromium per agent with `--user-data-dir=./isolated_data/<agent>`.
+// A profile's process group is orphaned when none of its processes descends
+// from a live bridge, e.g. after the bridge crashed.
+
+const TOP_N: usize = 5;
+const PROFILE_MARKER: &str = "isolated_data";
+const BRIDGE_NAMES: &[&str] = &["bridge", "browser_bridge"];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ProcessConfig {
+    #[serde(default)]
+    pub kill_orphans: bool, // SIGTERM orphaned Chromium groups instead of only reporting them
+}
+
+fn gb(bytes: u64) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0 * 1024.0)
+}
+
+fn name_of(p: &Process) -> String {
+    p.name().to_string_lossy().to_string()
+}
+
+fn is_chromium(p: &Process) -> bool {
+    name_of(p).to_lowercase().contains("chrom")
+}
+
+fn user_data_dir(p: &Process) -> Option<String> {
+    p.cmd().iter()
+        .filter_map(|a| a.to_str())
+        .find_map(|a| a.strip_prefix("--user-data-dir="))
+        .filter(|dir| dir.contains(PROFILE_MARKER))
+        .map(|dir| dir.to_string())
+}
+
+/// Whether any ancestor of `pid` is a running bridge process.
+fn has_bridge_ancestor(sys: &System, pid: Pid) -> bool {
+    let mut current = sys.process(pid).and_then(|p| p.parent());
+    while let Some(parent_pid) = current {
+        let Some(parent) = sys.process(parent_pid) else { return false };
+        let is_bridge = BRIDGE_NAMES.iter().any(|b| name_of(parent) == *b)
+            || parent.cmd().iter().any(|a| a.to_string_lossy().ends_with("browser_bridge.py"));
+        if is_bridge && parent.status() != ProcessStatus::Zombie {
+            return true;
+        }
+        current = parent.parent();
+    }
+    false
+}
+
+/// Every process, without the Linux threads sysinfo may list alongside them.
+fn processes(sys: &System) -> impl Iterator<Item = &Process> {
+    sys.processes().values().filter(|p| p.thread_kind().is_none())
+}
+
+fn top_by<F: Fn(&Process) -> f64>(sys: &System, key: F) -> Vec<&Process> {
+    let mut procs: Vec<&Process> = processes(sys).collect();
+    procs.sort_by(|a, b| key(b).total_cmp(&key(a)));
+    procs.truncate(TOP_N);
+    procs
+}
+
+pub struct ProcessRule;
+impl AuditRule for ProcessRule {
+    fn id(&self) -> &'static str {
+        "processes"
+    }
+
+    fn description(&self) -> &'static str {
+        "Top CPU/memory consumers, load, swap, zombies and orphaned Chromium."
+    }
+
+    fn thresholds(&self) -> &'static [(&'static str, f64)] {
+        &[("load_per_core", 2.0), ("swap_warning_pct", 50.0), ("swap_critical_pct", 80.0)]
+    }
+
+    fn run(&self, ctx: &SentryAudit) -> (Vec<AuditFinding>, Vec<AuditRecommendation>) {
+        let mut findings = vec![];
+        let mut recs = vec![];
+
+        // CPU usage needs two refreshes spaced by the minimum update interval
+        let refresh = ProcessRefreshKind::nothing()
+            .with_memory()
+            .with_cpu()
+            .with_cmd(UpdateKind::OnlyIfNotSet); // No tasks: threads would count as zombies and orphans
+        let mut sys = System::new();
+        sys.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh);
+        thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        sys.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh);
+        sys.refresh_memory();
+        let cpus = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1) as f64;
+
+        // Top consumers
+        let by_mem: Vec<String> = top_by(&sys, |p| p.memory() as f64).iter()
+            .map(|p| format!("{} ({:.2}GB, pid {})", name_of(p), gb(p.memory()), p.pid()))
+            .collect();
+        let by_cpu: Vec<String> = top_by(&sys, |p| p.cpu_usage() as f64).iter()
+            .map(|p| format!("{} ({:.0}%, pid {})", name_of(p), p.cpu_usage(), p.pid()))
+            .collect();
+        findings.push(AuditFinding::new("Top Memory Consumers", by_mem.join(", "), Severity::Info));
+        findings.push(AuditFinding::new("Top CPU Consumers", by_cpu.join(", "), Severity::Info));
+
+        // Load and swap
+        let load = System::load_average();
+        let load_severity = if load.five > cpus * ctx.threshold(self, "load_per_core") { Severity::Warning } else { Severity::Ok };
+        findings.push(AuditFinding::new(
+            "Load Average",
+            format!("{:.2} / {:.2} / {:.2} (1/5/15 min) on {} cores", load.one, load.five, load.fifteen, cpus),
+            load_severity,
+        ));
+
+        let (swap_total, swap_used) = (sys.total_swap(), sys.used_swap());
+        if swap_total > 0 {
+            let pct = swap_used as f64 / swap_total as f64 * 100.0;
+            let severity = if pct > ctx.threshold(self, "swap_critical_pct") { Severity::Critical }
+                          else if pct > ctx.threshold(self, "swap_warning_pct") { Severity::Warning }
+                          else { Severity::Ok };
+            findings.push(AuditFinding::new(
+                "Swap Pressure",
+                format!("{:.1}% used ({:.1}GB/{:.1}GB)", pct, gb(swap_used), gb(swap_total)),
+                severity,
+            ));
+        }
+
+        // Zombies
+        let zombies: Vec<&Process> = processes(&sys)
+            .filter(|p| p.status() == ProcessStatus::Zombie)
+            .collect();
+        if !zombies.is_empty() {
+            let chromium = zombies.iter().filter(|p| is_chromium(p)).count();
+            let listed: Vec<String> = zombies.iter()
+                .take(10)
+                .map(|p| format!("{} (pid {}, parent {})", name_of(p), p.pid(),
+                    p.parent().map(|pp| pp.to_string()).unwrap_or_else(|| "?".into())))
+                .collect();
+            findings.push(AuditFinding::new(
+                "Zombie Processes",
+                format!("{} zombies ({} Chromium): {}", zombies.len(), chromium, listed.join(", ")),
+                if chromium > 0 || zombies.len() > 10 { Severity::Warning } else { Severity::Info },
+            ));
+        }
+
+        // Orphaned Chromium profiles
+        let mut profiles: BTreeMap<String, Vec<&Process>> = BTreeMap::new();
+        for p in processes(&sys).filter(|p| is_chromium(p) && p.status() != ProcessStatus::Zombie) {
+            if let Some(dir) = user_data_dir(p) {
+                profiles.entry(dir).or_default().push(p);
+            }
+        }
+        let orphans: Vec<(&String, &Vec<&Process>)> = profiles.iter()
+            .filter(|(_, procs)| !procs.iter().any(|p| has_bridge_ancestor(&sys, p.pid())))
+            .collect();
+
+        if !orphans.is_empty() {
+            let memory: u64 = orphans.iter().flat_map(|(_, procs)| procs.iter()).map(|p| p.memory()).sum();
+            let listed: Vec<String> = orphans.iter()
+                .map(|(dir, procs)| format!("{} ({} processes, {:.2}GB)", dir, procs.len(), gb(procs.iter().map(|p| p.memory()).sum())))
+                .collect();
+            findings.push(AuditFinding::new(
+                "Orphaned Chromium",
+                format!("{} profiles without a live bridge hold {:.2}GB: {}", orphans.len(), gb(memory), listed.join(", ")),
+                Severity::Warning,
+            ).with_paths(orphans.iter().map(|(dir, _)| dir.trim_start_matches("./").to_string()).collect()));
+
+            if ctx.config.processes.kill_orphans {
+                let killed = orphans.iter()
+                    .flat_map(|(_, procs)| procs.iter())
+                    .filter(|p| p.kill_with(Signal::Term).unwrap_or(false))
+                    .count();
+                findings.push(AuditFinding::new(
+                    "Orphans Terminated",
+                    format!("Sent SIGTERM to {} orphaned Chromium processes.", killed),
+                    Severity::Info,
+                ));
+            } else {
+                let pids: Vec<String> = orphans.iter()
+                    .flat_map(|(_, procs)| procs.iter())
+                    .filter(|p| !p.cmd().iter().any(|a| a.to_string_lossy().starts_with("--type=")))
+                    .map(|p| p.pid().to_string())
+                    .collect();
+                recs.push(AuditRecommendation::new(format!(
+                    "Kill orphaned Chromium (kill {}) or enable processes.kill_orphans in the Sentry config.", pids.join(" "))));
+            }
+        }
+
+        (findings, recs)
+    }
+}
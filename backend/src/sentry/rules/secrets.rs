@@ -0,0 +1,312 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Read;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::LazyLock;
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::sentry::{AuditFinding, AuditRecommendation, AuditRule, SentryAudit, Severity};
+
+// --- Secret Leak Detection ---
+//
+// Scans files whose content is new since the last audit for credentials.
+// Matches are kept in the Sentry state per path, so a finding is repeated on
+// every audit until the file changes, is deleted or is allow-listed.
+// Matches can be silenced through `.sentry_secrets_allow` in the workspace
+// root: each line is either a gitignore-style path pattern or
+// `hash:<fingerprint>` as printed in the finding.
+
+pub const ALLOW_FILE: &str = ".sentry_secrets_allow";
+const MAX_MATCHES_LISTED: usize = 5;
+
+struct Detector {
+    kind: &'static str,
+    pattern: Regex,
+    group: usize, // capture group holding the secret itself
+}
+
+static DETECTORS: LazyLock<Vec<Detector>> = LazyLock::new(|| {
+    let d = |kind, pattern: &str, group| Detector { kind, pattern: Regex::new(pattern).unwrap(), group };
+    vec![
+        d("AWS access key", r"\b((?:AKIA|ASIA)[0-9A-Z]{16})\b", 1),
+        d("AWS secret key", r#"(?i)aws_secret_access_key\s*[:=]\s*["']?([A-Za-z0-9/+=]{40})"#, 1),
+        d("GCP API key", r"\b(AIza[0-9A-Za-z_\-]{35})", 1),
+        d("GCP service account key", r#""private_key_id"\s*:\s*"([0-9a-f]{40})""#, 1),
+        d("Anthropic API key", r"\b(sk-ant-[A-Za-z0-9_\-]{20,})", 1),
+        d("OpenAI API key", r"\b(sk-(?:proj-|svcacct-)?[A-Za-z0-9_\-]{20,})", 1),
+        d("Private key block", r"(-----BEGIN (?:[A-Z]+ )?PRIVATE KEY(?: BLOCK)?-----)", 1),
+    ]
+});
+
+static ASSIGNMENT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)(?:key|secret|token|passw(?:or)?d|credential)[a-z0-9_]*["']?\s*[:=]\s*["']?([A-Za-z0-9+/=_\-]{20,})"#).unwrap()
+});
+
+/// One detected credential; only the redacted preview is ever stored.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SecretMatch {
+    kind: String,
+    line: usize,
+    preview: String,
+    fingerprint: String,
+}
+
+fn entropy(s: &str) -> f64 {
+    let mut counts = [0usize; 256];
+    s.bytes().for_each(|b| counts[b as usize] += 1);
+    let len = s.len() as f64;
+    counts.iter()
+        .filter(|&&c| c > 0)
+        .map(|&c| {
+            let p = c as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Keeps a short prefix and suffix so the secret can be recognised but not reused.
+fn redact(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    if chars.len() <= 8 {
+        return "*".repeat(chars.len());
+    }
+    let head: String = chars[..4].iter().collect();
+    let tail: String = chars[chars.len() - 2..].iter().collect();
+    format!("{}{}{}", head, "*".repeat((chars.len() - 6).min(8)), tail)
+}
+
+fn fingerprint(secret: &str) -> String {
+    blake3::hash(secret.as_bytes()).to_hex()[..16].to_string()
+}
+
+fn is_env_file(name: &str) -> bool {
+    (name == ".env" || name.starts_with(".env."))
+        && !name.ends_with(".example") && !name.ends_with(".sample") && !name.ends_with(".template")
+}
+
+fn read_text(path: &Path, max_size: u64) -> Option<String> {
+    let file = fs::File::open(path).ok()?;
+    if file.metadata().ok()?.len() > max_size {
+        return None;
+    }
+    let mut bytes = vec![];
+    file.take(max_size).read_to_end(&mut bytes).ok()?;
+    if bytes.iter().take(8192).any(|&b| b == 0) {
+        return None; // binary
+    }
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// `min_entropy` is the Shannon entropy (bits per char) above which an assigned value looks random.
+fn scan_text(text: &str, env_file: bool, min_entropy: f64) -> Vec<SecretMatch> {
+    let mut found: Vec<SecretMatch> = vec![];
+    let mut push = |kind: &str, line, secret: &str| {
+        let fingerprint = fingerprint(secret);
+        if !found.iter().any(|m| m.fingerprint == fingerprint) {
+            found.push(SecretMatch { kind: kind.to_string(), line, preview: redact(secret), fingerprint });
+        }
+    };
+
+    for (i, line) in text.lines().enumerate() {
+        let mut matched_line = false;
+        for detector in DETECTORS.iter() {
+            for caps in detector.pattern.captures_iter(line) {
+                let secret = &caps[detector.group];
+                if detector.kind == "OpenAI API key" && secret.starts_with("sk-ant-") {
+                    continue;
+                }
+                push(detector.kind, i + 1, secret);
+                matched_line = true;
+            }
+        }
+        if matched_line {
+            continue;
+        }
+
+        if env_file {
+            if let Some((key, value)) = line.split_once('=')
+                && !key.trim_start().starts_with('#')
+            {
+                let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+                if !value.is_empty() {
+                    push(".env value", i + 1, value);
+                }
+            }
+        } else {
+            for caps in ASSIGNMENT.captures_iter(line) {
+                let secret = &caps[1];
+                if entropy(secret) >= min_entropy {
+                    push("High-entropy string", i + 1, secret);
+                }
+            }
+        }
+    }
+    found
+}
+
+struct AllowList {
+    paths: Gitignore,
+    hashes: Vec<String>,
+}
+
+impl AllowList {
+    fn load(workspace: &Path) -> Self {
+        let mut builder = GitignoreBuilder::new(workspace);
+        let mut hashes = vec![];
+        if let Ok(content) = fs::read_to_string(workspace.join(ALLOW_FILE)) {
+            for line in content.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+                match line.strip_prefix("hash:") {
+                    Some(hash) => hashes.push(hash.trim().to_string()),
+                    None => {
+                        let _ = builder.add_line(None, line);
+                    }
+                }
+            }
+        }
+        Self { paths: builder.build().unwrap_or_else(|_| Gitignore::empty()), hashes }
+    }
+
+    fn allows_path(&self, rel_path: &str) -> bool {
+        self.paths.matched_path_or_any_parents(rel_path, false).is_ignore()
+    }
+
+    fn allows_match(&self, m: &SecretMatch) -> bool {
+        self.hashes.iter().any(|h| h.len() >= 8 && m.fingerprint.starts_with(h.as_str()))
+    }
+}
+
+/// Files tracked by git, listed once per repository with `git ls-files -z`
+/// and kept for one audit run.
+#[derive(Default)]
+struct TrackedFiles {
+    repos: HashMap<PathBuf, Option<HashSet<PathBuf>>>, // None when git could not list the repo
+}
+
+impl TrackedFiles {
+    /// Whether `path` is tracked by whichever git repository contains it.
+    fn contains(&mut self, path: &Path) -> bool {
+        let Some(root) = path.ancestors().skip(1).find(|dir| dir.join(".git").exists()) else {
+            return false;
+        };
+        self.repos.entry(root.to_path_buf())
+            .or_insert_with(|| list_tracked(root))
+            .as_ref()
+            .is_some_and(|files| files.contains(path))
+    }
+}
+
+fn list_tracked(root: &Path) -> Option<HashSet<PathBuf>> {
+    let output = Command::new("git").arg("ls-files").arg("-z").current_dir(root).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(output.stdout.split(|&b| b == 0)
+        .filter(|name| !name.is_empty())
+        .map(|name| root.join(std::ffi::OsStr::from_bytes(name)))
+        .collect())
+}
+
+pub struct SecretsRule;
+impl AuditRule for SecretsRule {
+    fn id(&self) -> &'static str {
+        "secrets"
+    }
+
+    fn description(&self) -> &'static str {
+        "Credentials in new or modified files, reported until removed or allow-listed."
+    }
+
+    fn thresholds(&self) -> &'static [(&'static str, f64)] {
+        &[("max_file_kb", 1024.0), ("min_entropy", 4.0)]
+    }
+
+    fn run(&self, ctx: &SentryAudit) -> (Vec<AuditFinding>, Vec<AuditRecommendation>) {
+        let mut findings = vec![];
+        let mut recs = vec![];
+
+        let allow = AllowList::load(&ctx.workspace);
+        let max_size = (ctx.threshold(self, "max_file_kb") * 1024.0) as u64;
+        let min_entropy = ctx.threshold(self, "min_entropy");
+        let changes = ctx.changes();
+        let mut tracked = TrackedFiles::default();
+        let mut tracked_leak = false;
+
+        // Rescan new content; earlier matches in unchanged files still stand
+        let mut known = ctx.secrets.lock().unwrap_or_else(|e| e.into_inner());
+        known.retain(|rel_path, _| ctx.current_snapshot.contains_key(rel_path));
+        for rel_path in changes.new_content() {
+            known.remove(rel_path);
+            if rel_path == ALLOW_FILE || allow.allows_path(rel_path) {
+                continue;
+            }
+            let path = ctx.workspace.join(rel_path);
+            let Some(text) = read_text(&path, max_size) else { continue };
+            let env_file = path.file_name().and_then(|n| n.to_str()).is_some_and(is_env_file);
+            let matches = scan_text(&text, env_file, min_entropy);
+            if !matches.is_empty() {
+                known.insert(rel_path.clone(), matches);
+            }
+        }
+
+        for (rel_path, matches) in known.iter() {
+            if allow.allows_path(rel_path) {
+                continue;
+            }
+            let matches: Vec<&SecretMatch> = matches.iter().filter(|m| !allow.allows_match(m)).collect();
+            if matches.is_empty() {
+                continue;
+            }
+
+            let is_tracked = tracked.contains(&ctx.workspace.join(rel_path));
+            tracked_leak |= is_tracked;
+            let mut listed: Vec<String> = matches.iter()
+                .take(MAX_MATCHES_LISTED)
+                .map(|m| format!("{} `{}` on line {} (hash:{})", m.kind, m.preview, m.line, m.fingerprint))
+                .collect();
+            if matches.len() > MAX_MATCHES_LISTED {
+                listed.push(format!("… and {} more", matches.len() - MAX_MATCHES_LISTED));
+            }
+
+            findings.push(AuditFinding::new(
+                format!("Possible Secret in {}", rel_path),
+                format!("{}{}", if is_tracked { "Tracked by git: " } else { "" }, listed.join("; ")),
+                if is_tracked { Severity::Critical } else { Severity::Warning },
+            ).with_paths(vec![rel_path.to_string()]));
+        }
+
+        if tracked_leak {
+            recs.push(AuditRecommendation::new("Rotate credentials committed to git and purge them from history."));
+        } else if !findings.is_empty() {
+            recs.push(AuditRecommendation::new(format!(
+                "Move secrets out of the workspace, or allow-list known false positives in {}.", ALLOW_FILE)));
+        }
+
+        (findings, recs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracked_files_are_listed_once_per_repository() {
+        let repo = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| Command::new("git").args(args).current_dir(repo.path()).output().unwrap();
+        git(&["init", "-q"]);
+        fs::create_dir_all(repo.path().join("sub dir")).unwrap();
+        fs::write(repo.path().join("sub dir/tracked.env"), "A=1").unwrap();
+        fs::write(repo.path().join("untracked.env"), "B=2").unwrap();
+        git(&["add", "sub dir/tracked.env"]);
+
+        let mut tracked = TrackedFiles::default();
+        assert!(tracked.contains(&repo.path().join("sub dir/tracked.env")));
+        assert!(!tracked.contains(&repo.path().join("untracked.env")));
+        assert_eq!(tracked.repos.len(), 1);
+    }
+}
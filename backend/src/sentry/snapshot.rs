@@ -0,0 +1,228 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+
+use super::rules::SecretMatch;
+
+/// Bumped whenever the persisted layout changes; older state files are discarded.
+pub const STATE_VERSION: u32 = 2;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FileMetadata {
+    pub size: u64,
+    pub mtime: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>, // BLAKE3 of the content, only when hashing is enabled
+}
+
+impl FileMetadata {
+    /// Whether the content differs. Falls back to size/mtime when either side has no hash.
+    pub fn content_changed(&self, other: &FileMetadata) -> bool {
+        match (&self.hash, &other.hash) {
+            (Some(a), Some(b)) => a != b,
+            _ => self.size != other.size || self.mtime != other.mtime,
+        }
+    }
+}
+
+/// Workspace-relative path -> metadata, sorted so persisted state diffs cleanly.
+pub type Snapshot = BTreeMap<String, FileMetadata>;
+
+/// Minimum spacing between growth samples, in seconds.
+pub const GROWTH_SAMPLE_INTERVAL: u64 = 15 * 60;
+/// Number of growth samples kept (12 hours at the default interval).
+pub const GROWTH_HISTORY_LEN: usize = 48;
+/// Smaller files are left out of the per-file size history.
+pub const GROWTH_MIN_TRACKED: u64 = 1024 * 1024;
+
+/// Workspace size at one point in time, used for growth trends.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GrowthSample {
+    pub taken: u64, // Unix seconds
+    pub total: u64, // Sum of all file sizes in the snapshot
+    pub sizes: BTreeMap<String, u64>, // Files of at least GROWTH_MIN_TRACKED bytes
+}
+
+impl GrowthSample {
+    pub fn capture(snapshot: &Snapshot, taken: u64) -> Self {
+        Self {
+            taken,
+            total: snapshot.values().map(|m| m.size).sum(),
+            sizes: snapshot.iter()
+                .filter(|(_, m)| m.size >= GROWTH_MIN_TRACKED)
+                .map(|(p, m)| (p.clone(), m.size))
+                .collect(),
+        }
+    }
+}
+
+/// Appends a sample unless the last one is more recent than GROWTH_SAMPLE_INTERVAL.
+pub fn record_sample(history: &mut Vec<GrowthSample>, snapshot: &Snapshot, now: u64) {
+    if history.last().is_some_and(|s| now < s.taken + GROWTH_SAMPLE_INTERVAL) {
+        return;
+    }
+    history.push(GrowthSample::capture(snapshot, now));
+    if history.len() > GROWTH_HISTORY_LEN {
+        history.drain(..history.len() - GROWTH_HISTORY_LEN);
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SentryState {
+    #[serde(default)]
+    pub version: u32,
+    pub timestamp: String,
+    pub files: Snapshot,
+    #[serde(default)]
+    pub history: Vec<GrowthSample>,
+    #[serde(default)]
+    pub secrets: SecretIndex,
+}
+
+/// Workspace-relative path -> credentials found the last time its content was scanned.
+pub type SecretIndex = BTreeMap<String, Vec<SecretMatch>>;
+
+pub fn hash_file(path: &Path) -> Option<String> {
+    let mut hasher = blake3::Hasher::new();
+    let file = fs::File::open(path).ok()?;
+    hasher.update_reader(file).ok()?;
+    Some(hasher.finalize().to_hex().to_string())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Deleted,
+    Renamed,
+}
+
+impl ChangeKind {
+    pub fn marker(&self) -> char {
+        match self {
+            ChangeKind::Created => '+',
+            ChangeKind::Modified => '~',
+            ChangeKind::Deleted => '-',
+            ChangeKind::Renamed => '>',
+        }
+    }
+}
+
+/// Differences between two snapshots.
+#[derive(Debug, Default)]
+pub struct ChangeSet {
+    pub created: Vec<String>,
+    pub modified: Vec<String>,
+    pub deleted: Vec<String>,
+    pub renamed: Vec<(String, String)>, // (from, to), detected via identical content hashes
+    pub touched: Vec<String>,           // new mtime, identical content
+}
+
+#[derive(Debug, Default)]
+pub struct DirChurn {
+    pub created: usize,
+    pub modified: usize,
+    pub deleted: usize,
+    pub renamed: usize,
+    pub changes: Vec<(ChangeKind, String)>,
+}
+
+impl DirChurn {
+    pub fn total(&self) -> usize {
+        self.created + self.modified + self.deleted + self.renamed
+    }
+}
+
+impl ChangeSet {
+    pub fn compute(previous: &Snapshot, current: &Snapshot) -> Self {
+        let mut set = ChangeSet::default();
+
+        for (path, cur) in current {
+            match previous.get(path) {
+                None => set.created.push(path.clone()),
+                Some(prev) if cur.content_changed(prev) => set.modified.push(path.clone()),
+                Some(prev) if cur.mtime != prev.mtime => set.touched.push(path.clone()),
+                Some(_) => {}
+            }
+        }
+        let mut deleted: Vec<String> = previous.keys()
+            .filter(|p| !current.contains_key(*p))
+            .cloned()
+            .collect();
+
+        // A deleted file whose content reappears under a new path is a rename/move
+        let mut deleted_by_hash: HashMap<&str, Vec<String>> = HashMap::new();
+        for path in &deleted {
+            if let Some(hash) = &previous[path].hash {
+                deleted_by_hash.entry(hash.as_str()).or_default().push(path.clone());
+            }
+        }
+        set.created.retain(|path| {
+            let Some(hash) = &current[path].hash else { return true };
+            match deleted_by_hash.get_mut(hash.as_str()).and_then(|v| v.pop()) {
+                Some(from) => {
+                    set.renamed.push((from, path.clone()));
+                    false
+                }
+                None => true,
+            }
+        });
+        deleted.retain(|p| !set.renamed.iter().any(|(from, _)| from == p));
+        set.deleted = deleted;
+
+        set
+    }
+
+    pub fn churn(&self) -> usize {
+        self.created.len() + self.modified.len() + self.deleted.len() + self.renamed.len()
+    }
+
+    /// Every change as (kind, display path), sorted by path.
+    pub fn entries(&self) -> Vec<(ChangeKind, String)> {
+        let mut out: Vec<(ChangeKind, String)> = self.created.iter().map(|p| (ChangeKind::Created, p.clone()))
+            .chain(self.modified.iter().map(|p| (ChangeKind::Modified, p.clone())))
+            .chain(self.deleted.iter().map(|p| (ChangeKind::Deleted, p.clone())))
+            .chain(self.renamed.iter().map(|(from, to)| (ChangeKind::Renamed, format!("{} -> {}", from, to))))
+            .collect();
+        out.sort_by(|a, b| a.1.cmp(&b.1));
+        out
+    }
+
+    /// Paths whose current content is new to the workspace (created, modified or moved in).
+    pub fn new_content(&self) -> impl Iterator<Item = &String> {
+        self.created.iter()
+            .chain(self.modified.iter())
+            .chain(self.renamed.iter().map(|(_, to)| to))
+    }
+
+    /// Groups changes by their top-level directory (files at the root share the "." group).
+    pub fn by_top_dir(&self) -> BTreeMap<String, DirChurn> {
+        fn top(p: &str) -> String {
+            match p.split_once('/') {
+                Some((dir, _)) => format!("{}/", dir),
+                None => ".".to_string(),
+            }
+        }
+
+        let mut dirs: BTreeMap<String, DirChurn> = BTreeMap::new();
+        let mut add = |key: String, kind: ChangeKind, display: String| {
+            let dir = dirs.entry(key).or_default();
+            match kind {
+                ChangeKind::Created => dir.created += 1,
+                ChangeKind::Modified => dir.modified += 1,
+                ChangeKind::Deleted => dir.deleted += 1,
+                ChangeKind::Renamed => dir.renamed += 1,
+            }
+            dir.changes.push((kind, display));
+        };
+
+        self.created.iter().for_each(|p| add(top(p), ChangeKind::Created, p.clone()));
+        self.modified.iter().for_each(|p| add(top(p), ChangeKind::Modified, p.clone()));
+        self.deleted.iter().for_each(|p| add(top(p), ChangeKind::Deleted, p.clone()));
+        self.renamed.iter().for_each(|(from, to)| add(top(to), ChangeKind::Renamed, format!("{} -> {}", from, to)));
+
+        dirs.values_mut().for_each(|d| d.changes.sort_by(|a, b| a.1.cmp(&b.1)));
+        dirs
+    }
+}
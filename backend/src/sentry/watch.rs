@@ -0,0 +1,267 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use walkdir::WalkDir;
+
+use crate::sentry::{AuditFinding, SentryAudit, Severity};
+
+// --- Real-time Watch Mode ---
+//
+// Keeps `current_snapshot` up to date from inotify events instead of walking
+// the workspace every cycle. Directories are watched one by one (not
+// recursively) so ignored trees like `node_modules` never consume watches.
+
+/// Quiet period that ends a burst of events (e.g. `git checkout`).
+const DEBOUNCE: Duration = Duration::from_secs(2);
+/// Upper bound on how long a continuous burst can delay evaluation.
+const MAX_BATCH_DELAY: Duration = Duration::from_secs(15);
+
+pub struct WorkspaceWatcher {
+    watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+    limit_reached: bool,
+}
+
+impl WorkspaceWatcher {
+    pub fn new(sentry: &SentryAudit) -> notify::Result<Self> {
+        let (tx, rx) = channel();
+        let watcher = notify::recommended_watcher(move |res| {
+            let _ = tx.send(res);
+        })?;
+        let mut this = Self { watcher, events: rx, limit_reached: false };
+        this.watch_tree(sentry, &sentry.workspace.clone());
+        Ok(this)
+    }
+
+    /// Adds a non-recursive watch for every non-ignored directory under `root`.
+    fn watch_tree(&mut self, sentry: &SentryAudit, root: &Path) {
+        let dirs = WalkDir::new(root).into_iter()
+            .filter_entry(|e| !sentry.is_ignored_entry(e.path(), e.file_type().is_dir()))
+            .flatten()
+            .filter(|e| e.file_type().is_dir());
+
+        for dir in dirs {
+            if self.limit_reached {
+                return;
+            }
+            if let Err(e) = self.watcher.watch(dir.path(), RecursiveMode::NonRecursive)
+                && matches!(e.kind, notify::ErrorKind::MaxFilesWatch)
+            {
+                self.limit_reached = true;
+            }
+        }
+    }
+
+    /// Blocks until a burst of events settles or `deadline` passes, returning the touched paths.
+    fn collect_batch(&self, deadline: Instant) -> Option<HashSet<PathBuf>> {
+        let mut paths = HashSet::new();
+        let mut batch_started: Option<Instant> = None;
+
+        loop {
+            let now = Instant::now();
+            let wait = match batch_started {
+                Some(start) if now.duration_since(start) >= MAX_BATCH_DELAY => return Some(paths),
+                Some(_) => DEBOUNCE,
+                None if now >= deadline => return Some(paths),
+                None => deadline - now,
+            };
+
+            match self.events.recv_timeout(wait) {
+                Ok(Ok(event)) => {
+                    if matches!(event.kind, EventKind::Access(_)) {
+                        continue;
+                    }
+                    batch_started.get_or_insert(now);
+                    paths.extend(event.paths);
+                }
+                Ok(Err(e)) => {
+                    if matches!(e.kind, notify::ErrorKind::MaxFilesWatch) {
+                        return None;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => return Some(paths),
+                Err(RecvTimeoutError::Disconnected) => return None,
+            }
+        }
+    }
+}
+
+/// Applies a batch of changed paths to the snapshot. Returns paths that were
+/// created after the last evaluation and are already gone again.
+fn apply_batch(sentry: &mut SentryAudit, watcher: &mut WorkspaceWatcher, paths: HashSet<PathBuf>) -> Vec<String> {
+    let mut short_lived = vec![];
+
+    if paths.iter().any(|p| SentryAudit::is_ignore_file(p)) {
+        sentry.ignore.invalidate();
+    }
+
+    for path in paths {
+        if sentry.is_ignored(&path) {
+            continue;
+        }
+        let rel_path = sentry.relative_path(&path);
+
+        if path.is_dir() {
+            watcher.watch_tree(sentry, &path);
+            sentry.scan_tree(&path);
+        } else if let Some(meta) = sentry.stat_file(&path, &rel_path) {
+            sentry.current_snapshot.insert(rel_path, meta);
+        } else {
+            // Gone: drop the file itself and anything that lived below it
+            let prefix = format!("{}/", rel_path);
+            let removed: Vec<String> = sentry.current_snapshot.keys()
+                .filter(|k| **k == rel_path || k.starts_with(&prefix))
+                .cloned()
+                .collect();
+            if removed.is_empty() && !sentry.previous_snapshot.contains_key(&rel_path) {
+                // Created and deleted within a single batch
+                short_lived.push(rel_path);
+            }
+            for key in removed {
+                sentry.current_snapshot.remove(&key);
+                if !sentry.previous_snapshot.contains_key(&key) {
+                    short_lived.push(key);
+                }
+            }
+        }
+    }
+
+    short_lived.sort();
+    short_lived.dedup();
+    short_lived
+}
+
+fn limit_finding() -> AuditFinding {
+    AuditFinding::new(
+        "Watch Limit Reached",
+        "inotify watch limit exhausted; falling back to periodic full scans. Raise fs.inotify.max_user_watches to restore real-time mode.",
+        Severity::Warning,
+    ).with_rule("watch")
+}
+
+/// Periodic full walks, used when inotify is unavailable.
+fn run_polling(sentry: &mut SentryAudit, interval: Duration) -> ! {
+    loop {
+        let report = sentry.run(false);
+        sentry.publish(&report);
+        thread::sleep(interval);
+    }
+}
+
+/// Runs the daemon in watch mode: rules are evaluated after each settled
+/// burst of changes and at least once every `interval`.
+pub fn run_watch(sentry: &mut SentryAudit, interval: Duration) -> ! {
+    sentry.load_previous_state();
+    sentry.scan_filesystem();
+
+    let mut watcher = match WorkspaceWatcher::new(sentry) {
+        Ok(w) => w,
+        Err(e) => {
+            eprintln!("⚠️ Could not start filesystem watcher ({}). Using periodic scans.", e);
+            run_polling(sentry, interval);
+        }
+    };
+    if watcher.limit_reached {
+        sentry.notices.push(limit_finding());
+        drop(watcher);
+        run_polling(sentry, interval);
+    }
+
+    eprintln!("👁️ Watching {} for changes...", sentry.workspace.display());
+    let report = sentry.evaluate(false);
+    sentry.publish(&report);
+
+    let mut short_lived: Vec<String> = vec![];
+    let mut next_scheduled = Instant::now() + interval;
+
+    loop {
+        let Some(paths) = watcher.collect_batch(next_scheduled) else {
+            sentry.notices.push(limit_finding());
+            drop(watcher);
+            run_polling(sentry, interval);
+        };
+
+        let changed = !paths.is_empty();
+        short_lived.extend(apply_batch(sentry, &mut watcher, paths));
+
+        if watcher.limit_reached {
+            sentry.notices.push(limit_finding());
+            drop(watcher);
+            run_polling(sentry, interval);
+        }
+
+        let due = Instant::now() >= next_scheduled;
+        if !(due || changed && sentry.changes().churn() > 0) {
+            continue;
+        }
+
+        if !short_lived.is_empty() {
+            let sample: Vec<&str> = short_lived.iter().take(5).map(|s| s.as_str()).collect();
+            let finding = AuditFinding::new(
+                "Short-lived Files",
+                format!("{} files were created and removed between audits (e.g. {}).", short_lived.len(), sample.join(", ")),
+                Severity::Info,
+            );
+            sentry.notices.push(finding.with_rule("watch").with_paths(std::mem::take(&mut short_lived)));
+        }
+
+        let report = sentry.evaluate(false);
+        sentry.publish(&report);
+        next_scheduled = Instant::now() + interval;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sentry::config::SentryConfig;
+    use crate::sentry::ignore::{IgnoreConfig, IgnoreRules};
+
+    fn sentry_for(workspace: &Path) -> SentryAudit {
+        let mut sentry = SentryAudit::with_config(SentryConfig::default());
+        sentry.workspace = workspace.to_path_buf();
+        sentry.state_file = workspace.join(".sentry_state.json");
+        sentry.server_outputs = vec![workspace.join("sentry_reports.json"), workspace.join("sentry_reports.json.lock")];
+        sentry.ignore = IgnoreRules::new(workspace, IgnoreConfig { global_excludes: false, ..IgnoreConfig::default() });
+        sentry.scan_filesystem();
+        sentry.previous_snapshot = sentry.current_snapshot.clone();
+        sentry
+    }
+
+    fn next_batch(watcher: &WorkspaceWatcher) -> HashSet<PathBuf> {
+        watcher.collect_batch(Instant::now() + Duration::from_secs(5)).unwrap_or_default()
+    }
+
+    #[test]
+    fn publishing_a_report_does_not_retrigger_evaluation() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.md"), "hello").unwrap();
+        let mut sentry = sentry_for(dir.path());
+        let mut watcher = WorkspaceWatcher::new(&sentry).unwrap();
+
+        // What the server does when the daemon posts a report
+        std::fs::write(dir.path().join("sentry_reports.json.lock"), "").unwrap();
+        std::fs::write(dir.path().join("sentry_reports.json"), "[]").unwrap();
+        let paths = next_batch(&watcher);
+        assert!(!paths.is_empty(), "the watcher should see the report being written");
+
+        apply_batch(&mut sentry, &mut watcher, paths);
+        assert_eq!(sentry.changes().churn(), 0);
+    }
+
+    #[test]
+    fn workspace_edits_still_trigger_evaluation() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut sentry = sentry_for(dir.path());
+        let mut watcher = WorkspaceWatcher::new(&sentry).unwrap();
+
+        std::fs::write(dir.path().join("notes.md"), "hello").unwrap();
+        let paths = next_batch(&watcher);
+        apply_batch(&mut sentry, &mut watcher, paths);
+        assert_eq!(sentry.changes().churn(), 1);
+    }
+}
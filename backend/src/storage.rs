@@ -31,7 +31,7 @@ pub fn atomic_write_json<T: Serialize>(path: &str, data: &T) -> Result<()> {
     tmp.flush()?;
     tmp.as_file().sync_all()?;
     
-    tmp.persist(path).map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+    tmp.persist(path).map_err(std::io::Error::other)?;
     Ok(())
 }
 
@@ -56,6 +56,8 @@ impl Drop for FileLock {
 
 // --- Disk Logic ---
 
+/// Total size of the files below `path`. Inside a scan job, progress is
+/// reported and the walk stops early once the job is cancelled.
 pub fn calculate_dir_size(path: &str) -> u64 {
     WalkDir::new(path)
         .into_iter()
@@ -63,44 +65,120 @@ pub fn calculate_dir_size(path: &str) -> u64 {
         .filter_map(|e| e.metadata().ok())
         .filter(|m| m.is_file())
         .map(|m| m.len())
+        .take_while(|&len| crate::scan_jobs::record(1, len))
         .sum()
 }
 
-pub fn get_disk_usage(workspace_path: &str) -> Option<DiskStats> {
+/// Watched mounts (see `mounts::DiskConfig`); the workspace size is measured
+/// separately by a scan job and passed in.
+pub fn get_disk_usage(workspace_size: u64) -> Option<DiskStats> {
+    let mounts = crate::mounts::mount_stats(&crate::mounts::DiskConfig::load());
     let disks = Disks::new_with_refreshed_list();
-    if let Some(root) = disks.iter().find(|d| d.mount_point() == Path::new("/")) {
-        let total = root.total_space();
-        let available = root.available_space();
-        let used = total - available;
-        let usage_pct = (used as f64 / total as f64) * 100.0;
-        
-        let workspace_size = calculate_dir_size(workspace_path);
-
-        Some(DiskStats {
-            total,
-            used,
-            free: available,
-            usage_pct,
-            workspace_size,
-        })
-    } else {
-        None
-    }
+    let root = disks.iter().find(|d| d.mount_point() == Path::new("/"))?;
+    let total = root.total_space();
+    let available = root.available_space();
+    let used = total - available;
+    let usage_pct = (used as f64 / total as f64) * 100.0;
+
+    Some(DiskStats {
+        total,
+        used,
+        free: available,
+        usage_pct,
+        workspace_size,
+        mounts,
+    })
 }
 
 // --- Cleanup Logic ---
 
-pub fn delete_path(path_str: &str) -> std::io::Result<()> {
-    // Safety check: ensure we are only deleting from safe paths
-    if !path_str.contains(".gemini") {
-        return Err(std::io::Error::new(std::io::ErrorKind::PermissionDenied, "Unsafe path"));
+/// `path` with its parent directory canonicalized, provided it lies strictly
+/// inside one of `allowed_roots`. The final component is kept as is, so a
+/// symlink is checked (and later deleted) where it sits, not where it points.
+pub fn ensure_within(path: &Path, allowed_roots: &[String]) -> std::io::Result<std::path::PathBuf> {
+    let denied = || std::io::Error::new(std::io::ErrorKind::PermissionDenied, format!("{} is outside the allowed cleanup roots", path.display()));
+    path.symlink_metadata()?;
+    let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else { return Err(denied()) };
+    let parent = if parent.as_os_str().is_empty() { Path::new(".") } else { parent };
+    let resolved = parent.canonicalize()?.join(name);
+    let allowed = allowed_roots.iter()
+        .filter_map(|root| Path::new(root).canonicalize().ok())
+        .any(|root| resolved.starts_with(&root) && resolved != root);
+    if !allowed {
+        return Err(denied());
     }
+    Ok(resolved)
+}
 
-    let path = Path::new(path_str);
-    if path.is_file() {
-        std::fs::remove_file(path)
-    } else {
+/// Deletes `path_str` once `ensure_within` accepts it. A symlink is removed
+/// itself; its target is left alone.
+pub fn delete_path(path_str: &str, allowed_roots: &[String]) -> std::io::Result<()> {
+    let path = ensure_within(Path::new(path_str), allowed_roots)?;
+    if path.symlink_metadata()?.is_dir() {
         std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roots(dir: &Path) -> Vec<String> {
+        vec![dir.to_string_lossy().into_owned()]
+    }
+
+    #[test]
+    fn ensure_within_accepts_paths_inside_a_root() {
+        let root = tempfile::tempdir().unwrap();
+        let file = root.path().join("cache.bin");
+        std::fs::write(&file, b"x").unwrap();
+        assert_eq!(ensure_within(&file, &roots(root.path())).unwrap(), root.path().canonicalize().unwrap().join("cache.bin"));
+    }
+
+    #[test]
+    fn ensure_within_rejects_the_root_itself_and_missing_paths() {
+        let root = tempfile::tempdir().unwrap();
+        assert!(ensure_within(root.path(), &roots(root.path())).is_err());
+        assert!(ensure_within(&root.path().join("missing"), &roots(root.path())).is_err());
+    }
+
+    #[test]
+    fn ensure_within_rejects_dot_dot_escapes() {
+        let base = tempfile::tempdir().unwrap();
+        let root = base.path().join("root");
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        std::fs::write(base.path().join("outside"), b"x").unwrap();
+        assert!(ensure_within(&root.join("sub/../../outside"), &roots(&root)).is_err());
+        assert!(ensure_within(&root.join("sub/.."), &roots(&root)).is_err());
+    }
+
+    #[test]
+    fn ensure_within_rejects_paths_under_a_symlinked_directory() {
+        let base = tempfile::tempdir().unwrap();
+        let root = base.path().join("root");
+        let outside = base.path().join("outside");
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::write(outside.join("secret"), b"x").unwrap();
+        std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+        assert!(ensure_within(&root.join("link/secret"), &roots(&root)).is_err());
+    }
+
+    #[test]
+    fn delete_path_removes_a_symlink_but_not_its_target() {
+        let base = tempfile::tempdir().unwrap();
+        let root = base.path().join("root");
+        let outside = base.path().join("outside");
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::write(outside.join("keep"), b"x").unwrap();
+        let link = root.join("link");
+        std::os::unix::fs::symlink(&outside, &link).unwrap();
+
+        delete_path(&link.to_string_lossy(), &roots(&root)).unwrap();
+        assert!(link.symlink_metadata().is_err());
+        assert!(outside.join("keep").exists());
+    }
+}